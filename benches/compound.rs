@@ -0,0 +1,17 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use cmdx::{translate_compound_command, Os};
+
+fn bench_compound(c: &mut Criterion) {
+    let ascii = "ls -la /tmp && grep -i error log.txt | sort ; clear";
+    let unicode = "cat 'données été.txt' | grep -i 日本語 && echo \"naïve & café\" ; clear";
+
+    c.bench_function("compound_ascii", |b| {
+        b.iter(|| translate_compound_command(black_box(ascii), Os::Linux, Os::Windows))
+    });
+    c.bench_function("compound_unicode", |b| {
+        b.iter(|| translate_compound_command(black_box(unicode), Os::Linux, Os::Windows))
+    });
+}
+
+criterion_group!(benches, bench_compound);
+criterion_main!(benches);
@@ -0,0 +1,183 @@
+//! C FFI bindings, enabled with the `ffi` feature
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+
+use crate::translator::engine::translate_full;
+use crate::translator::os::Os;
+
+/// Translates a Windows command string to Linux using cmdx.
+/// Returns a newly allocated C string. Must be freed with free_string.
+///
+/// # Safety
+///
+/// `cmd` must be null or a valid pointer to a NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn preprocess_command(cmd: *const c_char) -> *mut c_char {
+    preprocess_command_os(cmd, 0, 1)
+}
+
+/// Translates a command string between the given operating systems.
+///
+/// OS codes are `0` = Windows, `1` = Linux, `2` = macOS, `3` = FreeBSD,
+/// `4` = OpenBSD, `5` = NetBSD, `6` = Solaris, `7` = Android, `8` = iOS.
+/// An unknown code, or a command that can't be translated, returns the
+/// original string unchanged. Returns a newly allocated C string that must
+/// be freed with free_string, or null if `cmd` is null.
+///
+/// # Safety
+///
+/// `cmd` must be null or a valid pointer to a NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn preprocess_command_os(
+    cmd: *const c_char,
+    from_os: c_int,
+    to_os: c_int,
+) -> *mut c_char {
+    if cmd.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let c_str = CStr::from_ptr(cmd);
+    let cmd_str = c_str.to_str().unwrap_or("");
+
+    // Perform translation; fallback to original if translation fails
+    let result = match (os_from_code(from_os), os_from_code(to_os)) {
+        (Some(from), Some(to)) => translate_full(cmd_str, from, to)
+            .map(|r| r.command)
+            .unwrap_or_else(|_| cmd_str.to_string()),
+        _ => cmd_str.to_string(),
+    };
+
+    // Convert Rust String to C string
+    let c_result = CString::new(result).unwrap_or_default();
+    c_result.into_raw()
+}
+
+/// Translates a command between the given operating systems, also returning
+/// its warnings.
+///
+/// OS codes are the same as for [`preprocess_command_os`]. An unknown code leaves the command unchanged and reports it as a warning.
+///
+/// The warnings are joined with newlines and written to `*warnings_out`
+/// (an empty string when there are none). Both the returned string and the
+/// warnings must be freed with free_string. Returns null if `cmd` is null.
+///
+/// # Safety
+///
+/// `cmd` must be null or a valid pointer to a NUL-terminated C string, and
+/// `warnings_out` must be null or valid for writing a single pointer.
+#[no_mangle]
+pub unsafe extern "C" fn preprocess_command_ex(
+    cmd: *const c_char,
+    from: c_int,
+    to: c_int,
+    warnings_out: *mut *mut c_char,
+) -> *mut c_char {
+    if !warnings_out.is_null() {
+        *warnings_out = std::ptr::null_mut();
+    }
+    if cmd.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let cmd_str = CStr::from_ptr(cmd).to_str().unwrap_or("");
+    let (command, warnings) = match (os_from_code(from), os_from_code(to)) {
+        (Some(from_os), Some(to_os)) => match translate_full(cmd_str, from_os, to_os) {
+            Ok(result) => (result.command, result.warnings),
+            Err(e) => (cmd_str.to_string(), vec![e.to_string()]),
+        },
+        _ => (cmd_str.to_string(), vec![format!("Unknown OS code in ({}, {})", from, to)]),
+    };
+
+    if !warnings_out.is_null() {
+        *warnings_out = CString::new(warnings.join("\n")).unwrap_or_default().into_raw();
+    }
+    CString::new(command).unwrap_or_default().into_raw()
+}
+
+/// Map an FFI OS code to an [`Os`]
+fn os_from_code(code: c_int) -> Option<Os> {
+    match code {
+        0 => Some(Os::Windows),
+        1 => Some(Os::Linux),
+        2 => Some(Os::MacOS),
+        3 => Some(Os::FreeBSD),
+        4 => Some(Os::OpenBSD),
+        5 => Some(Os::NetBSD),
+        6 => Some(Os::Solaris),
+        7 => Some(Os::Android),
+        8 => Some(Os::Ios),
+        _ => None,
+    }
+}
+
+/// Frees a C string previously allocated by preprocess_command,
+/// preprocess_command_os or preprocess_command_ex.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by one of this module's functions
+/// that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn free_string(s: *mut c_char) {
+    if s.is_null() {
+        return;
+    }
+    // Reconstruct CString so it gets dropped and memory freed
+    drop(CString::from_raw(s));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preprocess_command_ex_returns_warnings() {
+        let cmd = CString::new("dir /z").unwrap();
+        let mut warnings: *mut c_char = std::ptr::null_mut();
+        unsafe {
+            let result = preprocess_command_ex(cmd.as_ptr(), 0, 1, &mut warnings);
+            assert_eq!(CStr::from_ptr(result).to_str().unwrap(), "ls /z");
+            assert!(!warnings.is_null());
+            assert!(CStr::from_ptr(warnings).to_str().unwrap().contains("Flag '/z'"));
+            free_string(result);
+            free_string(warnings);
+        }
+    }
+
+    #[test]
+    fn test_preprocess_command_ex_null_inputs() {
+        let mut warnings: *mut c_char = std::ptr::null_mut();
+        unsafe {
+            assert!(preprocess_command_ex(std::ptr::null(), 0, 1, &mut warnings).is_null());
+            assert!(warnings.is_null());
+
+            let cmd = CString::new("cls").unwrap();
+            let result = preprocess_command_ex(cmd.as_ptr(), 0, 1, std::ptr::null_mut());
+            assert_eq!(CStr::from_ptr(result).to_str().unwrap(), "clear");
+            free_string(result);
+        }
+    }
+
+    fn preprocess_os(cmd: &str, from_os: c_int, to_os: c_int) -> String {
+        let cmd = CString::new(cmd).unwrap();
+        unsafe {
+            let result = preprocess_command_os(cmd.as_ptr(), from_os, to_os);
+            let translated = CStr::from_ptr(result).to_str().unwrap().to_string();
+            free_string(result);
+            translated
+        }
+    }
+
+    #[test]
+    fn test_preprocess_command_os() {
+        assert_eq!(preprocess_os("ls -la", 1, 0), "dir /a");
+        assert_eq!(preprocess_os("pbcopy", 2, 1), "xclip -selection clipboard");
+        assert_eq!(preprocess_os("cls", 0, 1), "clear");
+        // Unknown OS codes leave the command unchanged
+        assert_eq!(preprocess_os("ls -la", 1, 42), "ls -la");
+        assert_eq!(preprocess_os("ls -la", -1, 0), "ls -la");
+        assert!(unsafe { preprocess_command_os(std::ptr::null(), 1, 0) }.is_null());
+    }
+}
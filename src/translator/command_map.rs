@@ -3,11 +3,16 @@
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
 
+use super::engine::split_compound_command_for;
 use super::os::Os;
 
 /// Flag mapping between different operating systems
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FlagMapping {
     /// The source flag
     pub source: String,
@@ -36,18 +41,24 @@ impl FlagMapping {
 }
 
 /// Command mapping between different operating systems
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CommandMapping {
     /// Source command name
     pub source_cmd: String,
     /// Target command name
     pub target_cmd: String,
     /// Flag mappings for this command
+    #[serde(default)]
     pub flag_mappings: Vec<FlagMapping>,
     /// Whether to preserve unmapped flags
+    #[serde(default = "default_preserve_unmapped_flags")]
     pub preserve_unmapped_flags: bool,
     /// Notes about this command translation
+    #[serde(default)]
     pub notes: Option<String>,
+    /// Replacement target command when the target shell is PowerShell
+    #[serde(default)]
+    pub powershell_cmd: Option<String>,
 }
 
 impl CommandMapping {
@@ -58,6 +69,7 @@ impl CommandMapping {
             flag_mappings: Vec::new(),
             preserve_unmapped_flags: true,
             notes: None,
+            powershell_cmd: None,
         }
     }
 
@@ -66,12 +78,88 @@ impl CommandMapping {
         self
     }
 
+    pub fn with_notes(mut self, notes: &str) -> Self {
+        self.notes = Some(notes.to_string());
+        self
+    }
+
+    pub fn with_powershell(mut self, powershell_cmd: &str) -> Self {
+        self.powershell_cmd = Some(powershell_cmd.to_string());
+        self
+    }
+
     pub fn add_flag(&mut self, source: &str, target: &str) -> &mut Self {
         self.flag_mappings.push(FlagMapping::new(source, target));
         self
     }
+
+    /// Find the flag mapping for an argument
+    ///
+    /// An exact match always wins. Windows switches are case-insensitive, so
+    /// with `ignore_case` set `/S` also matches `/s`; Unix flags like `-r`
+    /// and `-R` are distinct and must match exactly.
+    pub fn find_flag(&self, arg: &str, ignore_case: bool) -> Option<&FlagMapping> {
+        self.flag_mappings.iter().find(|f| f.source == arg).or_else(|| {
+            self.flag_mappings
+                .iter()
+                .find(|f| ignore_case && f.source.eq_ignore_ascii_case(arg))
+        })
+    }
+
+    /// Find the longest flag an argument starts with, along with its value
+    ///
+    /// `/n:5` with a `/n:` mapping gives the mapping and `"5"`. The value is
+    /// returned with its original case.
+    pub fn find_flag_prefix<'a>(&self, arg: &'a str, ignore_case: bool) -> Option<(&FlagMapping, &'a str)> {
+        self.flag_mappings
+            .iter()
+            .filter(|f| match arg.get(..f.source.len()) {
+                Some(head) if ignore_case => head.eq_ignore_ascii_case(&f.source),
+                Some(head) => head == f.source,
+                None => false,
+            })
+            .max_by_key(|f| f.source.len())
+            .map(|f| (f, arg[f.source.len()..].trim_start_matches([':', '='])))
+    }
 }
 
+fn default_preserve_unmapped_flags() -> bool {
+    true
+}
+
+/// A user-supplied command mapping together with the OS pair it applies to
+///
+/// Deserialized from entries like
+/// `{"source_cmd": "dir", "target_cmd": "exa", "from_os": "windows", "to_os": "linux"}`;
+/// `flag_mappings`, `notes` and `preserve_unmapped_flags` are optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomMapping {
+    pub from_os: Os,
+    pub to_os: Os,
+    #[serde(flatten)]
+    pub mapping: CommandMapping,
+}
+
+/// Errors that can occur when loading custom mappings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MappingError {
+    /// The mappings file couldn't be read
+    Io(String),
+    /// The mappings file isn't a valid JSON list of mappings
+    Parse(String),
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingError::Io(msg) => write!(f, "Failed to read mappings file: {}", msg),
+            MappingError::Parse(msg) => write!(f, "Invalid mappings file: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MappingError {}
+
 /// Key for looking up command mappings
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct MappingKey {
@@ -90,6 +178,11 @@ impl MappingKey {
     }
 }
 
+lazy_static! {
+    /// User mappings registered at runtime, consulted before [`COMMAND_MAPPINGS`]
+    static ref CUSTOM_MAPPINGS: RwLock<HashMap<MappingKey, &'static CommandMapping>> = RwLock::new(HashMap::new());
+}
+
 lazy_static! {
     /// Global command mapping table
     pub static ref COMMAND_MAPPINGS: HashMap<MappingKey, CommandMapping> = {
@@ -357,8 +450,9 @@ lazy_static! {
                     FlagMapping::with_description("/s", "-r", "Recursive"),
                     FlagMapping::with_description("/n", "-n", "Line numbers"),
                     FlagMapping::with_description("/v", "-v", "Invert match"),
-                    FlagMapping::with_description("/c:", "-c", "Count matches"),
+                    FlagMapping::with_description("/c:", "-F -e", "Literal search string"),
                     FlagMapping::with_description("/r", "-E", "Regular expressions"),
+                    FlagMapping::with_description("/l", "-F", "Literal strings"),
                 ]),
         );
         
@@ -377,12 +471,14 @@ lazy_static! {
         // tasklist -> ps
         m.insert(
             MappingKey::new("tasklist", Os::Windows, Os::Linux),
-            CommandMapping::new("tasklist", "ps aux"),
+            CommandMapping::new("tasklist", "ps aux")
+                .with_notes("Column layout differs from tasklist; scripts parsing the output need updating"),
         );
         
         m.insert(
             MappingKey::new("tasklist", Os::Windows, Os::MacOS),
-            CommandMapping::new("tasklist", "ps aux"),
+            CommandMapping::new("tasklist", "ps aux")
+                .with_notes("Column layout differs from tasklist; scripts parsing the output need updating"),
         );
         
         // taskkill -> kill/pkill
@@ -392,7 +488,8 @@ lazy_static! {
                 .with_flags(vec![
                     FlagMapping::with_description("/f", "-9", "Force kill"),
                     FlagMapping::with_description("/pid", "", "Process ID (use directly)"),
-                    FlagMapping::with_description("/im", "-pkill ", "Image name -> use pkill"),
+                    FlagMapping::with_description("/im", "", "Image name (translated to pkill by the engine)"),
+                    FlagMapping::with_description("/t", "", "Kill child processes"),
                 ]),
         );
         
@@ -400,6 +497,7 @@ lazy_static! {
         m.insert(
             MappingKey::new("ipconfig", Os::Windows, Os::Linux),
             CommandMapping::new("ipconfig", "ip addr")
+                .with_notes("Output format differs; /all, /release and /renew have no ip addr equivalent")
                 .with_flags(vec![
                     FlagMapping::with_description("/all", "show", "Show all info"),
                     FlagMapping::with_description("/release", "", "Release DHCP"),
@@ -412,10 +510,35 @@ lazy_static! {
             CommandMapping::new("ipconfig", "ifconfig"),
         );
         
+        // route -> ip route (arguments rewritten by the engine)
+        m.insert(
+            MappingKey::new("route", Os::Windows, Os::Linux),
+            CommandMapping::new("route", "ip route")
+                .with_notes("Netmasks become CIDR prefixes and gateways use 'via'; interface indexes (if) and persistent routes (-p) have no ip route equivalent")
+                .with_flags(vec![
+                    FlagMapping::with_description("print", "show", "Show the routing table"),
+                    FlagMapping::with_description("add", "add", "Add a route"),
+                    FlagMapping::with_description("delete", "del", "Delete a route"),
+                    FlagMapping::with_description("change", "replace", "Modify a route"),
+                    FlagMapping::with_description("-p", "", "Persistent route"),
+                    FlagMapping::with_description("-f", "", "Clear gateway routes"),
+                    FlagMapping::with_description("-4", "-4", "IPv4 only"),
+                    FlagMapping::with_description("-6", "-6", "IPv6 only"),
+                ]),
+        );
+        
+        // netsh -> ip addr (only interface address changes are rewritten by the engine)
+        m.insert(
+            MappingKey::new("netsh", Os::Windows, Os::Linux),
+            CommandMapping::new("netsh", "ip addr")
+                .with_notes("netsh is not 1:1 with ip; only 'interface ip set address' is translated, and the change doesn't persist across reboots"),
+        );
+        
         // systeminfo -> uname -a
         m.insert(
             MappingKey::new("systeminfo", Os::Windows, Os::Linux),
-            CommandMapping::new("systeminfo", "uname -a && cat /etc/os-release"),
+            CommandMapping::new("systeminfo", "uname -a && cat /etc/os-release")
+                .with_notes("Only the kernel and distribution are shown; systeminfo's hardware, memory and patch details have no single Unix equivalent"),
         );
         
         // hostname -> hostname
@@ -439,7 +562,8 @@ lazy_static! {
         // attrib -> chmod/chattr
         m.insert(
             MappingKey::new("attrib", Os::Windows, Os::Linux),
-            CommandMapping::new("attrib", "chmod"),
+            CommandMapping::new("attrib", "chmod")
+                .with_notes("attrib's read-only flag only approximates removing write permission; hidden and system attributes have no Unix equivalent (hide files with a leading dot instead)"),
         );
         
         // fc -> diff
@@ -535,6 +659,7 @@ lazy_static! {
         m.insert(
             MappingKey::new("chkdsk", Os::Windows, Os::Linux),
             CommandMapping::new("chkdsk", "fsck")
+                .with_notes("fsck checks a device (e.g. /dev/sda1), not a drive letter, and must run as root on an unmounted filesystem")
                 .with_flags(vec![
                     FlagMapping::with_description("/f", "-y", "Fix errors automatically"),
                     FlagMapping::with_description("/r", "-c", "Locate bad sectors"),
@@ -586,6 +711,7 @@ lazy_static! {
                     FlagMapping::with_description("--sort=none", "", "Unsorted"),
                     FlagMapping::with_description("--color", "", "Color output (N/A)"),
                     FlagMapping::with_description("--color=auto", "", "Color when terminal"),
+                    FlagMapping::with_description("--color=always", "", "Always color"),
                     FlagMapping::with_description("--color=never", "", "No color"),
                 ]),
         );
@@ -603,6 +729,8 @@ lazy_static! {
                     FlagMapping::with_description("-S", "/o:s", "Sort by size"),
                     FlagMapping::with_description("-t", "/o:d", "Sort by time"),
                     FlagMapping::with_description("-r", "/o:-n", "Reverse order"),
+                    FlagMapping::with_description("-G", "", "Color output (N/A)"),
+                    FlagMapping::with_description("-F", "", "Append indicator"),
                 ]),
         );
         
@@ -620,6 +748,7 @@ lazy_static! {
                     FlagMapping::with_description("-u", "", "Update only"),
                     FlagMapping::with_description("-p", "", "Preserve attributes"),
                     FlagMapping::with_description("-a", "xcopy /s /e /h /k", "Archive mode"),
+                    FlagMapping::with_description("--archive", "xcopy /s /e /h /k", "Archive mode"),
                     FlagMapping::with_description("-l", "", "Create hard links"),
                     FlagMapping::with_description("-s", "", "Create symbolic links"),
                     FlagMapping::with_description("-L", "", "Follow symlinks"),
@@ -756,13 +885,15 @@ lazy_static! {
                     FlagMapping::with_description("-R", "/s", "Recursive search"),
                     FlagMapping::with_description("-n", "/n", "Show line numbers"),
                     FlagMapping::with_description("-v", "/v", "Invert match"),
-                    FlagMapping::with_description("-c", "/c:", "Count matches"),
                     FlagMapping::with_description("-l", "/m", "List files only"),
                     FlagMapping::with_description("-L", "", "List non-matching files"),
                     FlagMapping::with_description("-E", "/r", "Extended regex"),
+                    FlagMapping::with_description("-F", "/l", "Fixed strings"),
+                    FlagMapping::with_description("--fixed-strings", "/l", "Fixed strings"),
                     FlagMapping::with_description("-e", "", "Pattern"),
                     FlagMapping::with_description("-f", "/g:", "Patterns from file"),
-                    FlagMapping::with_description("-w", "", "Whole word"),
+                    FlagMapping::with_description("-w", "/r", "Whole word (pattern wrapped in \\< \\>)"),
+                    FlagMapping::with_description("--word-regexp", "/r", "Whole word (pattern wrapped in \\< \\>)"),
                     FlagMapping::with_description("-x", "/x", "Whole line"),
                     FlagMapping::with_description("-o", "", "Only matching"),
                     FlagMapping::with_description("-h", "", "No filename"),
@@ -774,6 +905,7 @@ lazy_static! {
                     FlagMapping::with_description("-B", "", "Before context"),
                     FlagMapping::with_description("-C", "", "Context lines"),
                     FlagMapping::with_description("--color", "", "Color output"),
+                    FlagMapping::with_description("--line-buffered", "", "Line buffering"),
                     FlagMapping::with_description("--include", "", "Include pattern"),
                     FlagMapping::with_description("--exclude", "", "Exclude pattern"),
                 ]),
@@ -788,16 +920,28 @@ lazy_static! {
                     FlagMapping::with_description("-R", "/s", "Recursive"),
                     FlagMapping::with_description("-n", "/n", "Line numbers"),
                     FlagMapping::with_description("-v", "/v", "Invert match"),
-                    FlagMapping::with_description("-c", "/c:", "Count"),
                     FlagMapping::with_description("-l", "/m", "List files"),
                     FlagMapping::with_description("-E", "/r", "Extended regex"),
+                    FlagMapping::with_description("-F", "/l", "Fixed strings"),
+                    FlagMapping::with_description("--fixed-strings", "/l", "Fixed strings"),
+                    FlagMapping::with_description("--line-buffered", "", "Line buffering"),
                 ]),
         );
         
         // ps -> tasklist
         m.insert(
             MappingKey::new("ps", Os::Linux, Os::Windows),
-            CommandMapping::new("ps", "tasklist"),
+            CommandMapping::new("ps", "tasklist")
+                .with_notes("Column layout differs from ps; scripts parsing the output need updating")
+                .with_flags(vec![
+                    // tasklist always lists every process
+                    FlagMapping::with_description("aux", "", "All processes, user format"),
+                    FlagMapping::with_description("-aux", "", "All processes, user format"),
+                    FlagMapping::with_description("ax", "", "All processes"),
+                    FlagMapping::with_description("-ef", "", "All processes, full format"),
+                    FlagMapping::with_description("-e", "", "All processes"),
+                    FlagMapping::with_description("-A", "", "All processes"),
+                ]),
         );
         
         // kill -> taskkill
@@ -829,6 +973,7 @@ lazy_static! {
         m.insert(
             MappingKey::new("ip", Os::Linux, Os::Windows),
             CommandMapping::new("ip", "ipconfig")
+                .with_notes("Only address display is supported; ip subcommands that change configuration are not translated")
                 .with_flags(vec![
                     FlagMapping::with_description("addr", "/all", "Show addresses"),
                     FlagMapping::with_description("link", "", "Link info"),
@@ -840,10 +985,12 @@ lazy_static! {
         m.insert(
             MappingKey::new("uname", Os::Linux, Os::Windows),
             CommandMapping::new("uname", "systeminfo")
+                .with_notes("systeminfo prints a full system report rather than uname's single line")
                 .with_flags(vec![
                     FlagMapping::with_description("-a", "", "All info"),
                     FlagMapping::with_description("-r", "", "Release"),
-                ]),
+                ])
+                .with_powershell("Get-CimInstance Win32_OperatingSystem"),
         );
         
         // env/printenv -> set
@@ -877,7 +1024,8 @@ lazy_static! {
         // less/more -> more
         m.insert(
             MappingKey::new("less", Os::Linux, Os::Windows),
-            CommandMapping::new("less", "more"),
+            CommandMapping::new("less", "more")
+                .with_notes("more can't scroll backwards or search like less"),
         );
         
         // which -> where
@@ -894,18 +1042,21 @@ lazy_static! {
         // touch -> type nul >
         m.insert(
             MappingKey::new("touch", Os::Linux, Os::Windows),
-            CommandMapping::new("touch", "type nul >"),
+            CommandMapping::new("touch", "type nul >")
+                .with_notes("type nul > truncates an existing file instead of updating its timestamp"),
         );
         
         // head/tail -> more (limited)
         m.insert(
             MappingKey::new("head", Os::Linux, Os::Windows),
-            CommandMapping::new("head", "more"),
+            CommandMapping::new("head", "more")
+                .with_notes("more pages through the whole file; it can't stop after the first lines"),
         );
         
         m.insert(
             MappingKey::new("tail", Os::Linux, Os::Windows),
-            CommandMapping::new("tail", "more"),
+            CommandMapping::new("tail", "more")
+                .with_notes("more can't show only the last lines or follow a growing file"),
         );
         
         // ping -> ping (different flags)
@@ -958,28 +1109,33 @@ lazy_static! {
         m.insert(
             MappingKey::new("wget", Os::Linux, Os::Windows),
             CommandMapping::new("wget", "curl -O")
+                .with_notes("curl -O saves under the remote file name but doesn't follow redirects without -L; wget's recursive, mirroring and resume options are not translated")
                 .with_flags(vec![
                     FlagMapping::with_description("-O", "-o", "Output file"),
                     FlagMapping::with_description("-q", "-s", "Quiet/silent"),
                 ]),
         );
         
-        // df -> wmic logicaldisk
+        // df -> wmic logicaldisk (wmic is deprecated; Get-PSDrive under PowerShell)
         m.insert(
             MappingKey::new("df", Os::Linux, Os::Windows),
-            CommandMapping::new("df", "wmic logicaldisk get size,freespace,caption"),
+            CommandMapping::new("df", "wmic logicaldisk get size,freespace,caption")
+                .with_notes("Sizes are reported in bytes per drive letter with no human-readable (-h) form, and mounted filesystems other than drives aren't listed; wmic is deprecated on recent Windows")
+                .with_powershell("Get-PSDrive -PSProvider FileSystem"),
         );
         
         // du -> dir (approximation)
         m.insert(
             MappingKey::new("du", Os::Linux, Os::Windows),
-            CommandMapping::new("du", "dir /s"),
+            CommandMapping::new("du", "dir /s")
+                .with_notes("dir /s lists every file with a total at the end; it has no per-directory summary or human-readable sizes"),
         );
         
         // ln -> mklink
         m.insert(
             MappingKey::new("ln", Os::Linux, Os::Windows),
             CommandMapping::new("ln", "mklink")
+                .with_notes("mklink takes the link name before the target and creates symbolic links only with admin rights or developer mode")
                 .with_flags(vec![
                     FlagMapping::with_description("-s", "", "Symbolic link (default in mklink)"),
                 ]),
@@ -988,13 +1144,96 @@ lazy_static! {
         // man -> help
         m.insert(
             MappingKey::new("man", Os::Linux, Os::Windows),
-            CommandMapping::new("man", "help"),
+            CommandMapping::new("man", "help")
+                .with_notes("help only documents built-in cmd.exe commands"),
         );
         
         // ============================================================
         // macOS specific additions
         // ============================================================
         
+        // ls -> ls (GNU color flags to BSD -G)
+        m.insert(
+            MappingKey::new("ls", Os::Linux, Os::MacOS),
+            CommandMapping::new("ls", "ls")
+                .with_flags(vec![
+                    FlagMapping::with_description("--color", "-G", "Color output"),
+                    FlagMapping::with_description("--color=auto", "-G", "Color when terminal"),
+                    FlagMapping::with_description("--color=always", "-G", "Always color"),
+                    FlagMapping::with_description("--color=never", "", "No color (default in BSD ls)"),
+                    FlagMapping::with_description("--classify", "-F", "Append indicator"),
+                ]),
+        );
+        
+        // ls -> ls (BSD -G means color, GNU -G means no group)
+        m.insert(
+            MappingKey::new("ls", Os::MacOS, Os::Linux),
+            CommandMapping::new("ls", "ls")
+                .with_flags(vec![
+                    FlagMapping::with_description("-G", "--color=auto", "Color output"),
+                ]),
+        );
+        
+        // sed -> sed (GNU -r is BSD -E; -i is rewritten by the engine)
+        m.insert(
+            MappingKey::new("sed", Os::Linux, Os::MacOS),
+            CommandMapping::new("sed", "sed")
+                .with_flags(vec![
+                    FlagMapping::with_description("-r", "-E", "Extended regular expressions"),
+                    FlagMapping::with_description("--regexp-extended", "-E", "Extended regular expressions"),
+                    FlagMapping::with_description("--quiet", "-n", "Suppress automatic printing"),
+                    FlagMapping::with_description("--silent", "-n", "Suppress automatic printing"),
+                    FlagMapping::with_description("--expression", "-e", "Add a script"),
+                ]),
+        );
+        
+        // date -> date (GNU long options to BSD short ones)
+        m.insert(
+            MappingKey::new("date", Os::Linux, Os::MacOS),
+            CommandMapping::new("date", "date")
+                .with_flags(vec![
+                    FlagMapping::with_description("--utc", "-u", "Coordinated Universal Time"),
+                    FlagMapping::with_description("--universal", "-u", "Coordinated Universal Time"),
+                    FlagMapping::with_description("-I", "+%Y-%m-%d", "ISO 8601 date"),
+                    FlagMapping::with_description("--iso-8601", "+%Y-%m-%d", "ISO 8601 date"),
+                    FlagMapping::with_description("--rfc-email", "-R", "RFC 5322 format"),
+                    FlagMapping::with_description("--reference", "-r", "Modification time of a file"),
+                ])
+                .with_notes("BSD date adjusts with -v and parses with -j -f; GNU -d strings are not translated"),
+        );
+        
+        m.insert(
+            MappingKey::new("date", Os::MacOS, Os::Linux),
+            CommandMapping::new("date", "date")
+                .with_flags(vec![
+                    FlagMapping::with_description("-j", "", "Don't set the clock (GNU never sets it without -s)"),
+                ])
+                .with_notes("BSD -v adjustments and -f parsing have no GNU flag; use date -d"),
+        );
+        
+        // stat -> stat (GNU -c FORMAT is BSD -f FORMAT)
+        m.insert(
+            MappingKey::new("stat", Os::Linux, Os::MacOS),
+            CommandMapping::new("stat", "stat")
+                .with_flags(vec![
+                    FlagMapping::with_description("-c", "-f", "Custom format"),
+                    FlagMapping::with_description("--format", "-f", "Custom format"),
+                    FlagMapping::with_description("--printf", "-f", "Custom format"),
+                    FlagMapping::with_description("--dereference", "-L", "Follow symlinks"),
+                ])
+                .with_notes("Format specifiers differ: GNU %s (size) is BSD %z, GNU %Y (mtime) is BSD %m"),
+        );
+        
+        m.insert(
+            MappingKey::new("stat", Os::MacOS, Os::Linux),
+            CommandMapping::new("stat", "stat")
+                .with_flags(vec![
+                    FlagMapping::with_description("-f", "-c", "Custom format"),
+                    FlagMapping::with_description("-x", "", "Verbose output (GNU default)"),
+                ])
+                .with_notes("Format specifiers differ: BSD %z (size) is GNU %s, BSD %m (mtime) is GNU %Y"),
+        );
+        
         // open -> start (macOS to Windows)
         m.insert(
             MappingKey::new("open", Os::MacOS, Os::Windows),
@@ -1021,7 +1260,8 @@ lazy_static! {
         // Note: Requires PowerShell. For cmd.exe, there's no direct equivalent.
         m.insert(
             MappingKey::new("pbpaste", Os::MacOS, Os::Windows),
-            CommandMapping::new("pbpaste", "powershell -command Get-Clipboard"),
+            CommandMapping::new("pbpaste", "powershell -command Get-Clipboard")
+                .with_powershell("Get-Clipboard"),
         );
         
         // Linux xclip -> pbcopy/pbpaste (Linux to macOS)
@@ -1102,6 +1342,91 @@ lazy_static! {
                 ]),
         );
         
+        // ============================================================
+        // PowerShell cmdlet mappings (Windows -> Unix)
+        // ============================================================
+        
+        for unix in [Os::Linux, Os::MacOS] {
+            m.insert(
+                MappingKey::new("Get-ChildItem", Os::Windows, unix),
+                CommandMapping::new("Get-ChildItem", "ls")
+                    .with_flags(vec![
+                        FlagMapping::with_description("-Recurse", "-R", "Recursive listing"),
+                        FlagMapping::with_description("-Force", "-a", "Include hidden items"),
+                        FlagMapping::with_description("-Name", "-1", "Names only"),
+                        FlagMapping::with_description("-Path", "", "Path parameter name"),
+                    ]),
+            );
+            
+            m.insert(
+                MappingKey::new("Remove-Item", Os::Windows, unix),
+                CommandMapping::new("Remove-Item", "rm")
+                    .with_flags(vec![
+                        FlagMapping::with_description("-Recurse", "-r", "Recursive delete"),
+                        FlagMapping::with_description("-Force", "-f", "Delete read-only and hidden items"),
+                        FlagMapping::with_description("-Confirm", "-i", "Prompt before each delete"),
+                        FlagMapping::with_description("-Path", "", "Path parameter name"),
+                    ]),
+            );
+            
+            m.insert(
+                MappingKey::new("Copy-Item", Os::Windows, unix),
+                CommandMapping::new("Copy-Item", "cp")
+                    .with_flags(vec![
+                        FlagMapping::with_description("-Recurse", "-r", "Copy directories recursively"),
+                        FlagMapping::with_description("-Force", "-f", "Overwrite read-only items"),
+                        FlagMapping::with_description("-Path", "", "Path parameter name"),
+                        FlagMapping::with_description("-Destination", "", "Destination parameter name"),
+                    ]),
+            );
+            
+            m.insert(
+                MappingKey::new("Move-Item", Os::Windows, unix),
+                CommandMapping::new("Move-Item", "mv")
+                    .with_flags(vec![
+                        FlagMapping::with_description("-Force", "-f", "Overwrite existing items"),
+                        FlagMapping::with_description("-Path", "", "Path parameter name"),
+                        FlagMapping::with_description("-Destination", "", "Destination parameter name"),
+                    ]),
+            );
+            
+            m.insert(
+                MappingKey::new("Get-Content", Os::Windows, unix),
+                CommandMapping::new("Get-Content", "cat")
+                    .with_flags(vec![
+                        FlagMapping::with_description("-Path", "", "Path parameter name"),
+                    ]),
+            );
+            
+            m.insert(
+                MappingKey::new("Select-String", Os::Windows, unix),
+                CommandMapping::new("Select-String", "grep -i")
+                    .with_flags(vec![
+                        FlagMapping::with_description("-Pattern", "", "Pattern parameter name"),
+                        FlagMapping::with_description("-Path", "", "Path parameter name"),
+                        FlagMapping::with_description("-NotMatch", "-v", "Invert match"),
+                        FlagMapping::with_description("-SimpleMatch", "-F", "Literal match"),
+                        FlagMapping::with_description("-List", "-l", "First match per file"),
+                    ])
+                    .with_notes("Select-String is case-insensitive by default, so grep gets -i"),
+            );
+            
+            m.insert(
+                MappingKey::new("Get-Location", Os::Windows, unix),
+                CommandMapping::new("Get-Location", "pwd"),
+            );
+            
+            m.insert(
+                MappingKey::new("Clear-Host", Os::Windows, unix),
+                CommandMapping::new("Clear-Host", "clear"),
+            );
+            
+            m.insert(
+                MappingKey::new("Write-Output", Os::Windows, unix),
+                CommandMapping::new("Write-Output", "echo"),
+            );
+        }
+        
         // ============================================================
         // BSD specific mappings
         // ============================================================
@@ -1147,88 +1472,155 @@ lazy_static! {
 }
 
 /// Get a command mapping if it exists
+///
+/// Mappings added with [`register_custom_mappings`] take precedence over the
+/// built-in table.
 pub fn get_mapping(command: &str, from_os: Os, to_os: Os) -> Option<&'static CommandMapping> {
     let key = MappingKey::new(command, from_os, to_os);
-    COMMAND_MAPPINGS.get(&key)
+    let custom = CUSTOM_MAPPINGS.read().unwrap_or_else(PoisonError::into_inner).get(&key).copied();
+    custom.or_else(|| COMMAND_MAPPINGS.get(&key))
+}
+
+/// Load command mappings from a JSON file
+///
+/// The file holds a list of [`CustomMapping`] entries. Pass the result to
+/// [`register_custom_mappings`] to use them for translation.
+pub fn load_custom_mappings(path: &Path) -> Result<Vec<CustomMapping>, MappingError> {
+    let contents = fs::read_to_string(path).map_err(|e| MappingError::Io(format!("{}: {}", path.display(), e)))?;
+    serde_json::from_str(&contents).map_err(|e| MappingError::Parse(format!("{}: {}", path.display(), e)))
+}
+
+/// Add mappings that are checked before the built-in table
+///
+/// A later registration for the same command and OS pair replaces an earlier
+/// one. [`get_mapping`] hands out `'static` references, so registered
+/// mappings are never freed, including replaced ones. Registration is meant
+/// to happen once per process, e.g. at startup. Re-registering an identical
+/// mapping is free, but reloading changed mappings repeatedly grows memory.
+pub fn register_custom_mappings(mappings: Vec<CustomMapping>) {
+    let mut custom = CUSTOM_MAPPINGS.write().unwrap_or_else(PoisonError::into_inner);
+    for entry in mappings {
+        let key = MappingKey::new(&entry.mapping.source_cmd, entry.from_os, entry.to_os);
+        if custom.get(&key).is_some_and(|&existing| *existing == entry.mapping) {
+            continue;
+        }
+        custom.insert(key, Box::leak(Box::new(entry.mapping)));
+    }
+}
+
+/// Find what a single flag of a command becomes on another OS
+///
+/// Uses the same rules as command translation: an exact match wins (ignoring
+/// case for Windows switches), otherwise the longest flag the argument starts with is used
+/// and its value carried over, so `/n:5` with a `/n:` → `-n` mapping gives
+/// `-n 5`. Flags that are known but have no equivalent return an empty
+/// string; unknown commands or flags return `None`.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{find_flag_equivalent, Os};
+///
+/// assert_eq!(find_flag_equivalent("ping", "-n", Os::Windows, Os::Linux), Some("-c".to_string()));
+/// assert_eq!(find_flag_equivalent("ping", "-z", Os::Windows, Os::Linux), None);
+/// ```
+pub fn find_flag_equivalent(command: &str, flag: &str, from_os: Os, to_os: Os) -> Option<String> {
+    let mapping = get_mapping(command, from_os, to_os)?;
+    let ignore_case = from_os == Os::Windows;
+
+    if let Some(exact) = mapping.find_flag(flag, ignore_case) {
+        return Some(exact.target.clone());
+    }
+
+    let (prefix, value) = mapping.find_flag_prefix(flag, ignore_case)?;
+    if prefix.target.is_empty() || value.is_empty() {
+        Some(prefix.target.clone())
+    } else {
+        Some(format!("{} {}", prefix.target, value))
+    }
+}
+
+/// Commands that ship with Windows
+const WINDOWS_NATIVE_COMMANDS: &[&str] = &[
+    "dir", "copy", "xcopy", "move", "del", "erase", "rmdir", "rd",
+    "mkdir", "md", "type", "cls", "findstr", "find", "tasklist",
+    "taskkill", "ipconfig", "systeminfo", "hostname", "whoami", "set",
+    "attrib", "fc", "more", "ren", "rename", "tree", "sort", "where",
+    "ping", "tracert", "netstat", "chkdsk", "start", "clip", "shutdown",
+    "robocopy", "icacls", "takeown", "sfc", "dism", "wmic", "net",
+    "sc", "reg", "powershell", "cmd", "echo", "pause", "exit", "call",
+    "if", "for", "goto", "setlocal", "endlocal", "pushd", "popd",
+    "mklink", "assoc", "ftype", "path", "title", "color", "prompt",
+    "ver", "vol", "label", "format", "diskpart", "bcdedit", "bootrec",
+];
+
+/// Commands found on Linux and other Unix-like systems
+const UNIX_NATIVE_COMMANDS: &[&str] = &[
+    "ls", "cp", "mv", "rm", "cat", "clear", "grep", "ps", "kill",
+    "pkill", "ifconfig", "ip", "uname", "env", "printenv", "export",
+    "chmod", "chown", "chgrp", "diff", "less", "more", "which",
+    "whereis", "touch", "head", "tail", "ping", "traceroute", "ss",
+    "netstat", "tar", "gzip", "gunzip", "bzip2", "xz", "zip", "unzip",
+    "curl", "wget", "df", "du", "ln", "man", "info", "find", "locate",
+    "xdg-open", "xclip", "xsel", "shutdown", "reboot", "halt", "poweroff",
+    "systemctl", "service", "apt", "apt-get", "yum", "dnf", "pacman",
+    "zypper", "emerge", "pkg", "brew", "snap", "flatpak", "echo", "printf",
+    "test", "expr", "bc", "awk", "sed", "cut", "sort", "uniq", "wc",
+    "tr", "tee", "xargs", "date", "cal", "uptime", "who", "w", "last",
+    "id", "groups", "sudo", "su", "passwd", "useradd", "userdel", "usermod",
+    "groupadd", "groupdel", "crontab", "at", "jobs", "fg", "bg", "nohup",
+    "screen", "tmux", "ssh", "scp", "sftp", "rsync", "nc", "telnet",
+    "ftp", "nmap", "tcpdump", "iptables", "ufw", "firewalld", "mount",
+    "umount", "fdisk", "parted", "mkfs", "fsck", "dd", "lsblk", "blkid",
+    "free", "top", "htop", "vmstat", "iostat", "sar", "strace", "ltrace",
+    "gdb", "valgrind", "make", "gcc", "g++", "clang", "ld", "ar", "nm",
+    "objdump", "readelf", "ldd", "git", "svn", "hg", "cvs", "patch",
+    "alias", "unalias", "history", "source", "exit", "logout", "cd", "pwd",
+    "mkdir", "rmdir", "basename", "dirname", "realpath", "readlink", "stat",
+    "file", "strings", "hexdump", "od", "xxd", "base64", "md5sum", "sha1sum",
+    "sha256sum", "openssl", "gpg", "dmesg", "journalctl", "logger", "syslog",
+];
+
+/// Commands found on macOS (BSD userland plus macOS tools)
+const MACOS_NATIVE_COMMANDS: &[&str] = &[
+    "ls", "cp", "mv", "rm", "cat", "clear", "grep", "ps", "kill",
+    "pkill", "ifconfig", "uname", "env", "printenv", "export",
+    "chmod", "chown", "chgrp", "diff", "less", "more", "which",
+    "whereis", "touch", "head", "tail", "ping", "traceroute",
+    "netstat", "tar", "gzip", "gunzip", "bzip2", "xz", "zip", "unzip",
+    "curl", "df", "du", "ln", "man", "find", "locate", "mdfind",
+    "open", "pbcopy", "pbpaste", "say", "caffeinate", "osascript",
+    "defaults", "launchctl", "diskutil", "hdiutil", "sw_vers", "system_profiler",
+    "softwareupdate", "spctl", "codesign", "xcode-select", "xcrun",
+    "brew", "port", "shutdown", "reboot", "halt", "echo", "printf",
+    "test", "expr", "bc", "awk", "sed", "cut", "sort", "uniq", "wc",
+    "tr", "tee", "xargs", "date", "cal", "uptime", "who", "w", "last",
+    "id", "groups", "sudo", "su", "passwd", "dscl", "dscacheutil",
+    "crontab", "at", "jobs", "fg", "bg", "nohup", "screen", "tmux",
+    "ssh", "scp", "sftp", "rsync", "nc", "telnet", "ftp", "nmap",
+    "tcpdump", "pfctl", "mount", "umount", "dd",
+    "top", "vm_stat", "fs_usage", "dtrace", "lldb", "make", "clang",
+    "git", "svn", "hg", "patch", "alias", "unalias", "history", "source",
+    "exit", "logout", "cd", "pwd", "mkdir", "rmdir", "basename", "dirname",
+    "realpath", "readlink", "stat", "file", "strings", "hexdump", "od",
+    "xxd", "base64", "md5", "shasum", "openssl", "security", "keychain",
+];
+
+/// Native command list for an OS, empty for [`Os::Unknown`]
+fn native_commands(os: Os) -> &'static [&'static str] {
+    match os {
+        Os::Windows => WINDOWS_NATIVE_COMMANDS,
+        Os::Linux | Os::FreeBSD | Os::OpenBSD | Os::NetBSD | Os::Solaris | Os::Android => UNIX_NATIVE_COMMANDS,
+        Os::MacOS | Os::Ios => MACOS_NATIVE_COMMANDS,
+        Os::Unknown => &[],
+    }
 }
 
 /// Check if a command is native to a specific OS
 /// Returns true if the command is known to be a native command for that OS
 pub fn is_native_command(command: &str, os: Os) -> bool {
     let cmd_lower = command.to_lowercase();
-    
-    match os {
-        Os::Windows => {
-            // Windows native commands
-            matches!(cmd_lower.as_str(),
-                "dir" | "copy" | "xcopy" | "move" | "del" | "erase" | "rmdir" | "rd" |
-                "mkdir" | "md" | "type" | "cls" | "findstr" | "find" | "tasklist" |
-                "taskkill" | "ipconfig" | "systeminfo" | "hostname" | "whoami" | "set" |
-                "attrib" | "fc" | "more" | "ren" | "rename" | "tree" | "sort" | "where" |
-                "ping" | "tracert" | "netstat" | "chkdsk" | "start" | "clip" | "shutdown" |
-                "robocopy" | "icacls" | "takeown" | "sfc" | "dism" | "wmic" | "net" |
-                "sc" | "reg" | "powershell" | "cmd" | "echo" | "pause" | "exit" | "call" |
-                "if" | "for" | "goto" | "setlocal" | "endlocal" | "pushd" | "popd" |
-                "mklink" | "assoc" | "ftype" | "path" | "title" | "color" | "prompt" |
-                "ver" | "vol" | "label" | "format" | "diskpart" | "bcdedit" | "bootrec"
-            )
-        }
-        Os::Linux | Os::FreeBSD | Os::OpenBSD | Os::NetBSD | Os::Solaris | Os::Android => {
-            // Unix/Linux native commands
-            matches!(cmd_lower.as_str(),
-                "ls" | "cp" | "mv" | "rm" | "cat" | "clear" | "grep" | "ps" | "kill" |
-                "pkill" | "ifconfig" | "ip" | "uname" | "env" | "printenv" | "export" |
-                "chmod" | "chown" | "chgrp" | "diff" | "less" | "more" | "which" |
-                "whereis" | "touch" | "head" | "tail" | "ping" | "traceroute" | "ss" |
-                "netstat" | "tar" | "gzip" | "gunzip" | "bzip2" | "xz" | "zip" | "unzip" |
-                "curl" | "wget" | "df" | "du" | "ln" | "man" | "info" | "find" | "locate" |
-                "xdg-open" | "xclip" | "xsel" | "shutdown" | "reboot" | "halt" | "poweroff" |
-                "systemctl" | "service" | "apt" | "apt-get" | "yum" | "dnf" | "pacman" |
-                "zypper" | "emerge" | "pkg" | "brew" | "snap" | "flatpak" | "echo" | "printf" |
-                "test" | "expr" | "bc" | "awk" | "sed" | "cut" | "sort" | "uniq" | "wc" |
-                "tr" | "tee" | "xargs" | "date" | "cal" | "uptime" | "who" | "w" | "last" |
-                "id" | "groups" | "sudo" | "su" | "passwd" | "useradd" | "userdel" | "usermod" |
-                "groupadd" | "groupdel" | "crontab" | "at" | "jobs" | "fg" | "bg" | "nohup" |
-                "screen" | "tmux" | "ssh" | "scp" | "sftp" | "rsync" | "nc" | "telnet" |
-                "ftp" | "nmap" | "tcpdump" | "iptables" | "ufw" | "firewalld" | "mount" |
-                "umount" | "fdisk" | "parted" | "mkfs" | "fsck" | "dd" | "lsblk" | "blkid" |
-                "free" | "top" | "htop" | "vmstat" | "iostat" | "sar" | "strace" | "ltrace" |
-                "gdb" | "valgrind" | "make" | "gcc" | "g++" | "clang" | "ld" | "ar" | "nm" |
-                "objdump" | "readelf" | "ldd" | "git" | "svn" | "hg" | "cvs" | "patch" |
-                "alias" | "unalias" | "history" | "source" | "exit" | "logout" | "cd" | "pwd" |
-                "mkdir" | "rmdir" | "basename" | "dirname" | "realpath" | "readlink" | "stat" |
-                "file" | "strings" | "hexdump" | "od" | "xxd" | "base64" | "md5sum" | "sha1sum" |
-                "sha256sum" | "openssl" | "gpg" | "dmesg" | "journalctl" | "logger" | "syslog"
-            )
-        }
-        Os::MacOS | Os::Ios => {
-            // macOS native commands (BSD-based plus macOS specific)
-            matches!(cmd_lower.as_str(),
-                "ls" | "cp" | "mv" | "rm" | "cat" | "clear" | "grep" | "ps" | "kill" |
-                "pkill" | "ifconfig" | "uname" | "env" | "printenv" | "export" |
-                "chmod" | "chown" | "chgrp" | "diff" | "less" | "more" | "which" |
-                "whereis" | "touch" | "head" | "tail" | "ping" | "traceroute" |
-                "netstat" | "tar" | "gzip" | "gunzip" | "bzip2" | "xz" | "zip" | "unzip" |
-                "curl" | "df" | "du" | "ln" | "man" | "find" | "locate" | "mdfind" |
-                "open" | "pbcopy" | "pbpaste" | "say" | "caffeinate" | "osascript" |
-                "defaults" | "launchctl" | "diskutil" | "hdiutil" | "sw_vers" | "system_profiler" |
-                "softwareupdate" | "spctl" | "codesign" | "xcode-select" | "xcrun" |
-                "brew" | "port" | "shutdown" | "reboot" | "halt" | "echo" | "printf" |
-                "test" | "expr" | "bc" | "awk" | "sed" | "cut" | "sort" | "uniq" | "wc" |
-                "tr" | "tee" | "xargs" | "date" | "cal" | "uptime" | "who" | "w" | "last" |
-                "id" | "groups" | "sudo" | "su" | "passwd" | "dscl" | "dscacheutil" |
-                "crontab" | "at" | "jobs" | "fg" | "bg" | "nohup" | "screen" | "tmux" |
-                "ssh" | "scp" | "sftp" | "rsync" | "nc" | "telnet" | "ftp" | "nmap" |
-                "tcpdump" | "pfctl" | "mount" | "umount" | "dd" |
-                "top" | "vm_stat" | "fs_usage" | "dtrace" | "lldb" | "make" | "clang" |
-                "git" | "svn" | "hg" | "patch" | "alias" | "unalias" | "history" | "source" |
-                "exit" | "logout" | "cd" | "pwd" | "mkdir" | "rmdir" | "basename" | "dirname" |
-                "realpath" | "readlink" | "stat" | "file" | "strings" | "hexdump" | "od" |
-                "xxd" | "base64" | "md5" | "shasum" | "openssl" | "security" | "keychain"
-            )
-        }
-        Os::Unknown => false,
-    }
+    native_commands(os).contains(&cmd_lower.as_str())
 }
 
 /// Check if a command exists as a target in mappings TO a specific OS
@@ -1247,6 +1639,114 @@ pub fn is_target_command_for_os(command: &str, target_os: Os) -> bool {
         })
 }
 
+/// Check if a command needs root/administrator privileges on a specific OS
+///
+/// Based on a curated list of system administration commands; commands that
+/// only sometimes need elevation (e.g. `chmod`) are not included.
+pub fn is_privileged(command: &str, os: Os) -> bool {
+    let cmd_lower = command.to_lowercase();
+    let cmd = cmd_lower.as_str();
+
+    match os {
+        Os::Windows => matches!(
+            cmd,
+            "sfc" | "dism" | "chkdsk" | "diskpart" | "bcdedit" | "format" | "takeown" |
+            "sc" | "netsh" | "runas" | "vssadmin" | "wevtutil" | "manage-bde" | "fsutil"
+        ),
+        Os::Unknown => false,
+        _ => {
+            matches!(
+                cmd,
+                "sudo" | "su" | "doas" | "mount" | "umount" | "systemctl" | "service" |
+                "iptables" | "ip6tables" | "nft" | "ufw" | "fsck" | "mkfs" | "fdisk" |
+                "parted" | "modprobe" | "insmod" | "rmmod" | "useradd" | "userdel" |
+                "usermod" | "groupadd" | "groupdel" | "chown" | "chroot" | "reboot" |
+                "shutdown" | "halt" | "poweroff" | "sysctl" | "swapon" | "swapoff" |
+                "visudo" | "dmidecode" | "apt" | "apt-get" | "dnf" | "yum" | "pacman" |
+                "zypper" | "apk"
+            ) || (os == Os::MacOS && matches!(cmd, "launchctl" | "pfctl" | "softwareupdate" | "pmset"))
+        }
+    }
+}
+
+/// Check if a command line irreversibly deletes or overwrites data on an OS
+///
+/// Covers recursive deletes (`rm -rf`, `find -exec rm -r`, `del /s`, `rd /s`,
+/// `Remove-Item -Recurse`), disk formatting and raw device writes. Each segment
+/// of a compound line is checked, and a leading `sudo`/`doas` with its options
+/// is skipped. Runners can use this to ask for
+/// confirmation before executing a translated command.
+pub fn is_destructive(command_line: &str, os: Os) -> bool {
+    split_compound_command_for(command_line, os)
+        .iter()
+        .any(|segment| is_destructive_segment(segment, os))
+}
+
+fn is_destructive_segment(segment: &str, os: Os) -> bool {
+    // sudo/doas options are skipped along with the values some of them take
+    const SUDO_VALUE_OPTIONS: &[&str] = &["-u", "-g", "-p", "-C", "-D", "-r", "-t", "-U", "-T", "--user", "--group"];
+
+    let mut words = segment.split_whitespace().peekable();
+    while words.next_if(|w| w.eq_ignore_ascii_case("sudo") || *w == "doas").is_some() {
+        while let Some(option) = words.next_if(|w| w.starts_with('-')) {
+            if SUDO_VALUE_OPTIONS.contains(&option) {
+                words.next();
+            }
+        }
+    }
+    let Some(cmd) = words.next().map(str::to_lowercase) else { return false };
+    let args: Vec<String> = words.map(str::to_lowercase).collect();
+    let has = |flags: &[&str]| args.iter().any(|a| flags.contains(&a.as_str()));
+    // PowerShell accepts any unambiguous prefix of a parameter name
+    let recurse = args.iter().any(|a| a.len() >= 2 && "-recurse".starts_with(a.as_str()));
+
+    match os {
+        Os::Windows => match cmd.trim_end_matches(".exe") {
+            "format" | "diskpart" => true,
+            "del" | "erase" | "rd" | "rmdir" => has(&["/s"]) || recurse,
+            "remove-item" | "ri" => recurse,
+            "robocopy" => has(&["/mir", "/purge"]),
+            "cipher" => args.iter().any(|a| a.starts_with("/w")),
+            _ => false,
+        },
+        Os::Unknown => false,
+        _ => match cmd.as_str() {
+            // Everything after `--` is an operand, even if it looks like a flag
+            "rm" => args.iter().take_while(|a| *a != "--").any(|a| {
+                a == "--recursive"
+                    || (a.starts_with('-') && !a.starts_with("--") && a.contains('r'))
+            }),
+            "shred" | "wipefs" => true,
+            "dd" => args.iter().any(|a| a.starts_with("of=/dev/")),
+            // The command run by -exec/-ok ends at `;` or `+`
+            "find" => has(&["-delete"]) || args.iter().enumerate().any(|(i, a)| {
+                matches!(a.as_str(), "-exec" | "-execdir" | "-ok" | "-okdir") && {
+                    let command: Vec<&str> = args[i + 1..]
+                        .iter()
+                        .map(String::as_str)
+                        .take_while(|w| !matches!(*w, ";" | "\\;" | "+"))
+                        .collect();
+                    is_destructive_segment(&command.join(" "), os)
+                }
+            }),
+            _ => cmd.starts_with("mkfs"),
+        },
+    }
+}
+
+/// Check whether an exit code means success for a command
+///
+/// Most commands signal success with 0 only, but `robocopy` uses 0-7 for
+/// success variants (files copied, extra files found, ...) and 8+ for
+/// failures. Runners should use this instead of comparing against 0.
+pub fn is_success_exit_code(command: &str, code: i32) -> bool {
+    let name = command.split_whitespace().next().unwrap_or("").to_lowercase();
+    match name.trim_end_matches(".exe") {
+        "robocopy" => (0..8).contains(&code),
+        _ => code == 0,
+    }
+}
+
 /// Get all available commands for a specific OS transition
 pub fn get_available_commands(from_os: Os, to_os: Os) -> Vec<&'static str> {
     COMMAND_MAPPINGS
@@ -1256,6 +1756,158 @@ pub fn get_available_commands(from_os: Os, to_os: Os) -> Vec<&'static str> {
         .collect()
 }
 
+/// Complete a partial command name from the commands mapped for `from_os`
+///
+/// Backs tab completion in a shell: returns every source command with a
+/// mapping to any OS whose name starts with `prefix`, sorted and without
+/// duplicates. Windows names match regardless of case.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{complete_command, Os};
+///
+/// assert!(complete_command("di", Os::Windows).contains(&"dir"));
+/// ```
+pub fn complete_command(prefix: &str, from_os: Os) -> Vec<&'static str> {
+    let matches_prefix = |name: &str| match name.get(..prefix.len()) {
+        Some(head) if from_os == Os::Windows => head.eq_ignore_ascii_case(prefix),
+        Some(head) => head == prefix,
+        None => false,
+    };
+
+    let mut names: Vec<&'static str> = COMMAND_MAPPINGS
+        .iter()
+        .filter(|(key, mapping)| key.from_os == from_os && matches_prefix(&mapping.source_cmd))
+        .map(|(_, mapping)| mapping.source_cmd.as_str())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Find the mappings that produce a given target command
+///
+/// Searches the `from_os` to `to_os` mappings for entries whose target
+/// command's base name matches `target_cmd`'s (`ls -la` matches `ls`).
+/// Mappings aren't one-to-one, so every candidate is returned, sorted by
+/// source command; for example both `wget` and `curl` lead to `curl` on
+/// Windows.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{get_reverse_mapping, Os};
+///
+/// let sources = get_reverse_mapping("ls", Os::Windows, Os::Linux);
+/// assert!(sources.iter().any(|m| m.source_cmd == "dir"));
+/// ```
+pub fn get_reverse_mapping(target_cmd: &str, from_os: Os, to_os: Os) -> Vec<&'static CommandMapping> {
+    let Some(base) = target_cmd.split_whitespace().next() else {
+        return Vec::new();
+    };
+    let matches_base = |target: &str| {
+        let target = target.split_whitespace().next().unwrap_or("");
+        if to_os == Os::Windows { target.eq_ignore_ascii_case(base) } else { target == base }
+    };
+
+    let mut mappings: Vec<&'static CommandMapping> = COMMAND_MAPPINGS
+        .iter()
+        .filter(|(key, mapping)| key.from_os == from_os && key.to_os == to_os && matches_base(&mapping.target_cmd))
+        .map(|(_, mapping)| mapping)
+        .collect();
+    mappings.sort_by(|a, b| a.source_cmd.cmp(&b.source_cmd));
+    mappings
+}
+
+/// Mapping counts for one source/target OS pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PairCoverage {
+    pub from_os: Os,
+    pub to_os: Os,
+    /// Mapped source commands and how many flags each maps, sorted by command
+    pub commands: Vec<(String, usize)>,
+}
+
+impl PairCoverage {
+    /// Total number of flag mappings across all commands
+    pub fn flag_count(&self) -> usize {
+        self.commands.iter().map(|(_, flags)| flags).sum()
+    }
+}
+
+/// Native commands of an OS that no mapping translates from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnmappedCommands {
+    pub os: Os,
+    pub commands: Vec<String>,
+}
+
+/// Summary of the built-in mapping table, for deciding which mappings to add next
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MappingCoverage {
+    /// One entry per OS pair with at least one mapping
+    pub pairs: Vec<PairCoverage>,
+    /// Windows, Linux and macOS native commands without any mapping
+    pub unmapped: Vec<UnmappedCommands>,
+}
+
+impl fmt::Display for MappingCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pair in &self.pairs {
+            writeln!(
+                f,
+                "{} -> {}: {} commands, {} flags",
+                pair.from_os,
+                pair.to_os,
+                pair.commands.len(),
+                pair.flag_count()
+            )?;
+        }
+        for unmapped in &self.unmapped {
+            writeln!(f, "{} unmapped ({}): {}", unmapped.os, unmapped.commands.len(), unmapped.commands.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Summarize the built-in [`COMMAND_MAPPINGS`] table
+///
+/// Custom mappings are not included.
+pub fn coverage_report() -> MappingCoverage {
+    let os_index = |os: Os| Os::all().iter().position(|&o| o == os);
+
+    let mut pairs: HashMap<(Os, Os), Vec<(String, usize)>> = HashMap::new();
+    for (key, mapping) in COMMAND_MAPPINGS.iter() {
+        pairs
+            .entry((key.from_os, key.to_os))
+            .or_default()
+            .push((key.command.clone(), mapping.flag_mappings.len()));
+    }
+    let mut pairs: Vec<PairCoverage> = pairs
+        .into_iter()
+        .map(|((from_os, to_os), mut commands)| {
+            commands.sort();
+            PairCoverage { from_os, to_os, commands }
+        })
+        .collect();
+    pairs.sort_by_key(|p| (os_index(p.from_os), os_index(p.to_os)));
+
+    let unmapped = [Os::Windows, Os::Linux, Os::MacOS]
+        .into_iter()
+        .map(|os| UnmappedCommands {
+            os,
+            commands: native_commands(os)
+                .iter()
+                .filter(|cmd| !COMMAND_MAPPINGS.keys().any(|k| k.from_os == os && k.command == **cmd))
+                .map(|cmd| cmd.to_string())
+                .collect(),
+        })
+        .collect();
+
+    MappingCoverage { pairs, unmapped }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1308,6 +1960,42 @@ mod tests {
         assert!(mapping.is_none());
     }
 
+    #[test]
+    fn test_complete_command() {
+        let candidates = complete_command("di", Os::Windows);
+        assert!(candidates.contains(&"dir"));
+        assert!(candidates.iter().all(|c| c.to_lowercase().starts_with("di")));
+        assert!(candidates.windows(2).all(|w| w[0] < w[1]));
+
+        assert!(complete_command("DI", Os::Windows).contains(&"dir"));
+        assert!(complete_command("get-child", Os::Windows).contains(&"Get-ChildItem"));
+
+        let candidates = complete_command("l", Os::Linux);
+        assert!(candidates.contains(&"ls"));
+        assert!(!candidates.contains(&"dir"));
+        assert!(complete_command("LS", Os::Linux).is_empty());
+        assert!(complete_command("zzz", Os::Windows).is_empty());
+    }
+
+    #[test]
+    fn test_get_reverse_mapping() {
+        let sources: Vec<&str> = get_reverse_mapping("ls", Os::Windows, Os::Linux)
+            .iter()
+            .map(|m| m.source_cmd.as_str())
+            .collect();
+        assert!(sources.contains(&"dir"));
+        assert!(sources.windows(2).all(|w| w[0] <= w[1]));
+
+        // Several Unix commands lead to curl on Windows
+        let sources = get_reverse_mapping("curl -O https://example.com", Os::Linux, Os::Windows);
+        assert!(sources.iter().any(|m| m.source_cmd == "wget"));
+        assert!(sources.iter().any(|m| m.source_cmd == "curl"));
+
+        assert!(get_reverse_mapping("DIR", Os::Linux, Os::Windows).iter().any(|m| m.source_cmd == "ls"));
+        assert!(get_reverse_mapping("nonexistent", Os::Windows, Os::Linux).is_empty());
+        assert!(get_reverse_mapping("", Os::Windows, Os::Linux).is_empty());
+    }
+
     #[test]
     fn test_get_available_commands() {
         let commands = get_available_commands(Os::Windows, Os::Linux);
@@ -1360,4 +2048,171 @@ mod tests {
         // dir is a target command for Windows (from Linux -> Windows mappings)
         assert!(is_target_command_for_os("dir", Os::Windows));
     }
+
+    #[test]
+    fn test_is_privileged() {
+        assert!(is_privileged("mount", Os::Linux));
+        assert!(is_privileged("systemctl", Os::Linux));
+        assert!(is_privileged("pfctl", Os::MacOS));
+        assert!(is_privileged("diskpart", Os::Windows));
+        assert!(!is_privileged("echo", Os::Linux));
+        assert!(!is_privileged("ls", Os::Linux));
+        assert!(!is_privileged("dir", Os::Windows));
+    }
+
+    #[test]
+    fn test_is_success_exit_code() {
+        for code in 0..8 {
+            assert!(is_success_exit_code("robocopy src dst /e", code));
+        }
+        assert!(!is_success_exit_code("robocopy src dst", 8));
+        assert!(!is_success_exit_code("ROBOCOPY.EXE src dst", 16));
+        assert!(is_success_exit_code("copy a b", 0));
+        assert!(!is_success_exit_code("copy a b", 1));
+    }
+
+    #[test]
+    fn test_is_destructive_unix() {
+        assert!(is_destructive("rm -rf /tmp/foo", Os::Linux));
+        assert!(is_destructive("sudo rm -R build", Os::Linux));
+        assert!(is_destructive("rm --recursive build", Os::MacOS));
+        assert!(is_destructive("mkfs.ext4 /dev/sdb1", Os::Linux));
+        assert!(is_destructive("dd if=image.iso of=/dev/sdb", Os::Linux));
+        assert!(is_destructive("cd build && rm -fr out", Os::Linux));
+        assert!(!is_destructive("rm file.txt", Os::Linux));
+        assert!(!is_destructive("dd if=a of=b.img", Os::Linux));
+        assert!(!is_destructive("ls -r", Os::Linux));
+        assert!(!is_destructive("rm -f -- -r", Os::Linux));
+        assert!(is_destructive("sudo -u root rm -rf /", Os::Linux));
+        assert!(is_destructive("find . -name '*.o' -exec rm -rf {} +", Os::Linux));
+        assert!(!is_destructive("find . -exec ls -r {} +", Os::Linux));
+    }
+
+    #[test]
+    fn test_is_destructive_windows() {
+        assert!(is_destructive("del /s /q C:\\temp", Os::Windows));
+        assert!(is_destructive("RD /S build", Os::Windows));
+        assert!(is_destructive("format D:", Os::Windows));
+        assert!(is_destructive("robocopy src dst /MIR", Os::Windows));
+        assert!(!is_destructive("del file.txt", Os::Windows));
+        assert!(!is_destructive("dir /s", Os::Windows));
+        assert!(!is_destructive("rm -rf /", Os::Unknown));
+        assert!(!is_destructive("del /q file.txt", Os::Windows));
+        assert!(!is_destructive("rmdir /q empty", Os::Windows));
+        assert!(is_destructive("Remove-Item -Recurse -Force build", Os::Windows));
+        assert!(!is_destructive("Remove-Item file.txt", Os::Windows));
+    }
+
+    #[test]
+    fn test_find_flag_equivalent_ping() {
+        assert_eq!(find_flag_equivalent("ping", "-n", Os::Windows, Os::Linux), Some("-c".to_string()));
+        assert_eq!(find_flag_equivalent("ping", "-c", Os::Linux, Os::Windows), Some("-n".to_string()));
+        assert_eq!(find_flag_equivalent("ping", "-t", Os::Windows, Os::Linux), Some(String::new()));
+        assert_eq!(find_flag_equivalent("ping", "-z", Os::Windows, Os::Linux), None);
+    }
+
+    #[test]
+    fn test_find_flag_equivalent_grep_and_findstr() {
+        assert_eq!(find_flag_equivalent("grep", "-i", Os::Linux, Os::Windows), Some("/i".to_string()));
+        assert_eq!(find_flag_equivalent("findstr", "/c:", Os::Windows, Os::Linux), Some("-F -e".to_string()));
+        assert_eq!(find_flag_equivalent("findstr", "/C:Word", Os::Windows, Os::Linux), Some("-F -e Word".to_string()));
+        assert_eq!(find_flag_equivalent("findstr", "/I", Os::Windows, Os::Linux), Some("-i".to_string()));
+    }
+
+    #[test]
+    fn test_find_flag_equivalent_dir() {
+        assert_eq!(find_flag_equivalent("dir", "/s", Os::Windows, Os::Linux), Some("-R".to_string()));
+        assert_eq!(find_flag_equivalent("dir", "/o:-s", Os::Windows, Os::Linux), Some("--sort=size -r".to_string()));
+        assert_eq!(find_flag_equivalent("dir", "/z", Os::Windows, Os::Linux), None);
+        assert_eq!(find_flag_equivalent("nosuchcmd", "/s", Os::Windows, Os::Linux), None);
+    }
+
+    #[test]
+    fn test_load_custom_mappings_overrides_builtin() {
+        let path = std::env::temp_dir().join(format!("cmdx-mappings-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"[{"source_cmd": "dir", "target_cmd": "exa", "from_os": "windows", "to_os": "netbsd",
+                "flag_mappings": [{"source": "/s", "target": "--tree"}]}]"#,
+        )
+        .unwrap();
+        let mappings = load_custom_mappings(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(mappings.len(), 1);
+        assert!(mappings[0].mapping.preserve_unmapped_flags);
+
+        assert_eq!(get_mapping("dir", Os::Windows, Os::NetBSD).unwrap().target_cmd, "ls");
+        register_custom_mappings(mappings);
+        let mapping = get_mapping("DIR", Os::Windows, Os::NetBSD).unwrap();
+        assert_eq!(mapping.target_cmd, "exa");
+        assert_eq!(mapping.flag_mappings[0].target, "--tree");
+        // Other OS pairs keep the built-in mapping
+        assert_eq!(get_mapping("dir", Os::Windows, Os::FreeBSD).unwrap().target_cmd, "ls");
+    }
+
+    #[test]
+    fn test_register_custom_mappings_same_key_again() {
+        let custom = |target: &str| CustomMapping {
+            from_os: Os::Windows,
+            to_os: Os::Solaris,
+            mapping: CommandMapping::new("reloaded", target),
+        };
+
+        register_custom_mappings(vec![custom("first")]);
+        let first = get_mapping("reloaded", Os::Windows, Os::Solaris).unwrap();
+
+        // An identical mapping keeps the registered one rather than leaking a copy
+        register_custom_mappings(vec![custom("first")]);
+        assert!(std::ptr::eq(first, get_mapping("reloaded", Os::Windows, Os::Solaris).unwrap()));
+
+        // A changed mapping replaces it; the old reference stays valid
+        register_custom_mappings(vec![custom("second")]);
+        assert_eq!(get_mapping("reloaded", Os::Windows, Os::Solaris).unwrap().target_cmd, "second");
+        assert_eq!(first.target_cmd, "first");
+    }
+
+    #[test]
+    fn test_load_custom_mappings_errors() {
+        let missing = std::env::temp_dir().join("cmdx-no-such-mappings.json");
+        assert!(matches!(load_custom_mappings(&missing), Err(MappingError::Io(_))));
+
+        let path = std::env::temp_dir().join(format!("cmdx-bad-mappings-{}.json", std::process::id()));
+        fs::write(&path, r#"[{"source_cmd": "dir"}]"#).unwrap();
+        let result = load_custom_mappings(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(MappingError::Parse(_))));
+    }
+
+    #[test]
+    fn test_find_flag_case_rules() {
+        let mapping = get_mapping("dir", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(mapping.find_flag("/S", true).unwrap().target, "-R");
+        assert!(mapping.find_flag("/S", false).is_none());
+
+        let mapping = get_mapping("findstr", Os::Windows, Os::Linux).unwrap();
+        let (flag, value) = mapping.find_flag_prefix("/C:MixedCase", true).unwrap();
+        assert_eq!(flag.source, "/c:");
+        assert_eq!(value, "MixedCase");
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let report = coverage_report();
+
+        let pair = report
+            .pairs
+            .iter()
+            .find(|p| p.from_os == Os::Windows && p.to_os == Os::Linux)
+            .unwrap();
+        assert_eq!(pair.commands.len(), get_available_commands(Os::Windows, Os::Linux).len());
+        assert!(pair.commands.iter().any(|(cmd, flags)| cmd == "dir" && *flags > 0));
+        assert!(pair.flag_count() > 0);
+        assert_eq!(report.pairs[0].from_os, Os::Windows);
+
+        let windows = report.unmapped.iter().find(|u| u.os == Os::Windows).unwrap();
+        assert!(windows.commands.contains(&"robocopy".to_string()));
+        assert!(!windows.commands.contains(&"dir".to_string()));
+
+        assert!(report.to_string().contains("Windows -> Linux:"));
+    }
 }
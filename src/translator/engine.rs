@@ -1,11 +1,13 @@
 //! Translation engine - core logic for translating commands between operating systems
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fmt;
 
-use super::command_map::{get_mapping, is_native_command, is_target_command_for_os, CommandMapping};
-use super::os::Os;
-use super::path::{translate_path, is_windows_path, is_unix_path};
+use super::command_map::{get_mapping, is_native_command, is_privileged, is_target_command_for_os, CommandMapping};
+use super::env::translate_env_vars;
+use super::os::{Os, Shell};
+use super::path::{translate_path, translate_path_list, is_windows_path, is_unix_path};
 
 /// Result of a command translation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +24,34 @@ pub struct TranslationResult {
     pub warnings: Vec<String>,
     /// Whether any flags couldn't be translated
     pub had_unmapped_flags: bool,
+    /// The flags that couldn't be translated, as they appeared in the command
+    #[serde(default)]
+    pub unmapped_flags: Vec<String>,
+    /// Whether any shell constructs were passed through untranslated
+    #[serde(default)]
+    pub had_unsupported_constructs: bool,
+    /// Whether the command needs root/administrator privileges to run
+    #[serde(default)]
+    pub requires_privilege: bool,
+    /// How faithfully the command was translated
+    #[serde(default)]
+    pub confidence: Confidence,
+}
+
+/// How faithfully a [`TranslationResult`] reproduces the original command
+///
+/// Variants are ordered from most to least confident, so the lowest confidence
+/// of several results is their maximum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// Every part of the command was mapped, with nothing to note
+    #[default]
+    Exact,
+    /// Translated, but some flags weren't mapped or the mapping has caveats
+    Approximate,
+    /// Passed through unchanged, on the assumption it runs as written on the target
+    Passthrough,
 }
 
 impl TranslationResult {
@@ -33,7 +63,102 @@ impl TranslationResult {
             to_os,
             warnings: Vec::new(),
             had_unmapped_flags: false,
+            unmapped_flags: Vec::new(),
+            had_unsupported_constructs: false,
+            requires_privilege: false,
+            confidence: Confidence::Exact,
+        }
+    }
+
+    /// Add a warning
+    pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
+        self.warnings.push(warning.into());
+        self
+    }
+
+    /// Add several warnings, after any already present
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings.extend(warnings);
+        self
+    }
+
+    /// Record that some flags couldn't be translated
+    pub fn mark_unmapped_flags(mut self) -> Self {
+        self.had_unmapped_flags = true;
+        self
+    }
+
+    /// The translated command quoted as a single word for the given OS's shell
+    ///
+    /// Use this when embedding the result in another command line, e.g.
+    /// `sh -c <quoted>` or `cmd /c <quoted>`. Unix shells get single quotes
+    /// (with `'` written as `'\''`); cmd.exe gets double quotes with embedded
+    /// quotes doubled, and `%` doubled as batch files require.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmdx::{translate_command, Os};
+    ///
+    /// let result = translate_command("echo it's", Os::Windows, Os::Linux).unwrap();
+    /// assert_eq!(result.shell_escaped(Os::Linux), r"'echo it'\''s'");
+    /// ```
+    pub fn shell_escaped(&self, target_os: Os) -> String {
+        if target_os == Os::Windows {
+            format!("\"{}\"", self.command.replace('"', "\"\"").replace('%', "%%"))
+        } else {
+            format!("'{}'", self.command.replace('\'', r"'\''"))
+        }
+    }
+
+    /// The shell the translated command should be run in
+    ///
+    /// Based on the target OS rather than the host, so cross-OS results are
+    /// run correctly. Windows results that use PowerShell constructs (cmdlets
+    /// such as `Select-String` or `$env:` variables) need pwsh instead of
+    /// cmd.exe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmdx::{translate_command, Os, Shell};
+    ///
+    /// let result = translate_command("ls -la", Os::Linux, Os::Windows).unwrap();
+    /// assert_eq!(result.recommended_shell(), Shell::Cmd);
+    /// ```
+    pub fn recommended_shell(&self) -> Shell {
+        if self.to_os == Os::Windows && uses_powershell_constructs(&self.command) {
+            Shell::PowerShell
+        } else {
+            Shell::default_for(self.to_os)
+        }
+    }
+}
+
+/// Check whether a command line relies on PowerShell syntax
+fn uses_powershell_constructs(command: &str) -> bool {
+    if command.contains("$env:") {
+        return true;
+    }
+    split_compound_command(command).iter().any(|segment| {
+        segment
+            .split_whitespace()
+            .next()
+            .is_some_and(is_cmdlet_name)
+    })
+}
+
+/// Check for a PowerShell `Verb-Noun` cmdlet name such as `Get-ChildItem`
+fn is_cmdlet_name(word: &str) -> bool {
+    match word.split_once('-') {
+        Some((verb, noun)) => {
+            verb.len() > 1
+                && noun.starts_with(|c: char| c.is_ascii_uppercase())
+                && verb.starts_with(|c: char| c.is_ascii_uppercase())
+                && verb.chars().all(|c| c.is_ascii_alphabetic())
+                && noun.chars().all(|c| c.is_ascii_alphanumeric())
         }
+        None => false,
     }
 }
 
@@ -43,6 +168,48 @@ impl fmt::Display for TranslationResult {
     }
 }
 
+/// Options controlling how a command is translated
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranslationOptions {
+    /// Keep the original spacing between trailing operands
+    ///
+    /// By default arguments are re-joined with single spaces, which changes
+    /// the output of commands like `echo    aligned   text`.
+    pub preserve_whitespace: bool,
+    /// Shell the translated command will run in
+    ///
+    /// `None` uses the target OS default (cmd.exe on Windows). Selecting
+    /// `Shell::PowerShell` replaces deprecated `wmic` forms with cmdlets.
+    pub shell: Option<Shell>,
+    /// Target a BusyBox userland, as on Alpine-based Docker images
+    ///
+    /// GNU-only flags that BusyBox applets reject (`ls --color`, `grep -P`)
+    /// are dropped with a warning. Only applies to Unix-like targets.
+    #[serde(default)]
+    pub minimal_coreutils: bool,
+}
+
+/// GNU flags that BusyBox applets don't accept, by command
+///
+/// A flag also matches its `--flag=value` form.
+const BUSYBOX_UNSUPPORTED_FLAGS: &[(&str, &str)] = &[
+    ("ls", "--color"),
+    ("ls", "--group-directories-first"),
+    ("ls", "--time-style"),
+    ("ls", "--hyperlink"),
+    ("ls", "--quoting-style"),
+    ("grep", "-P"),
+    ("grep", "--perl-regexp"),
+    ("grep", "--color"),
+    ("grep", "--line-buffered"),
+    ("cp", "--reflink"),
+    ("cp", "--sparse"),
+    ("rm", "--preserve-root"),
+    ("rm", "--one-file-system"),
+    ("sort", "--parallel"),
+    ("df", "--output"),
+];
+
 /// Errors that can occur during translation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TranslationError {
@@ -54,6 +221,8 @@ pub enum TranslationError {
     InvalidOs(String),
     /// Same source and target OS
     SameOs,
+    /// Shell construct (control flow, heredoc, subshell...) that can't be translated
+    UnsupportedConstruct(String),
 }
 
 impl fmt::Display for TranslationError {
@@ -71,6 +240,9 @@ impl fmt::Display for TranslationError {
             TranslationError::SameOs => {
                 write!(f, "Source and target OS are the same")
             }
+            TranslationError::UnsupportedConstruct(construct) => {
+                write!(f, "Unsupported shell construct: {}", construct)
+            }
         }
     }
 }
@@ -80,18 +252,164 @@ impl std::error::Error for TranslationError {}
 /// Parse a command string into command name and arguments
 fn parse_command(input: &str) -> (String, Vec<String>) {
     let trimmed = input.trim();
-    let parts: Vec<&str> = trimmed.split_whitespace().collect();
+    let parts = split_arguments(trimmed);
     
     if parts.is_empty() {
         return (String::new(), Vec::new());
     }
     
     let command = parts[0].to_lowercase();
-    let args: Vec<String> = parts[1..].iter().map(|s| s.to_string()).collect();
+    let args: Vec<String> = parts[1..].to_vec();
     
     (command, args)
 }
 
+/// Split a command line on whitespace, keeping quoted sections together
+///
+/// Quotes are kept in the returned tokens so they are re-emitted as written.
+fn split_arguments(input: &str) -> Vec<String> {
+    split_argument_spans(input)
+        .into_iter()
+        .map(|(start, end)| input[start..end].to_string())
+        .collect()
+}
+
+/// Byte ranges of the whitespace-separated, quote-aware tokens in `input`
+fn split_argument_spans(input: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut quote: Option<char> = None;
+
+    for (i, c) in input.char_indices() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                start.get_or_insert(i);
+            }
+            None if c.is_whitespace() => {
+                if let Some(s) = start.take() {
+                    spans.push((s, i));
+                }
+            }
+            None => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+
+    if let Some(s) = start {
+        spans.push((s, input.len()));
+    }
+
+    spans
+}
+
+/// Check if a token looks like a flag for either OS family
+fn looks_like_flag(arg: &str) -> bool {
+    arg.starts_with('-') || (arg.starts_with('/') && arg.len() <= MAX_WINDOWS_FLAG_LEN)
+}
+
+/// Re-apply the original spacing of the trailing operands to a translated command
+///
+/// Only done when the translated command ends with exactly the same operand
+/// tokens as the original, so translated operands (e.g. paths) are left alone.
+fn restore_operand_whitespace(original: &str, translated: &str) -> String {
+    let spans = split_argument_spans(original);
+    // Operands after the last flag-looking token (the command itself is skipped)
+    let tail_start = spans
+        .iter()
+        .rposition(|&(s, e)| looks_like_flag(&original[s..e]))
+        .map_or(1, |i| i + 1)
+        .max(1);
+    if tail_start >= spans.len() {
+        return translated.to_string();
+    }
+
+    let tail: Vec<&str> = spans[tail_start..].iter().map(|&(s, e)| &original[s..e]).collect();
+    let translated_tokens = split_arguments(translated);
+    if translated_tokens.len() < tail.len() {
+        return translated.to_string();
+    }
+    let (head, translated_tail) = translated_tokens.split_at(translated_tokens.len() - tail.len());
+    if translated_tail.iter().zip(&tail).any(|(a, b)| a != b) {
+        return translated.to_string();
+    }
+
+    format!("{} {}", head.join(" "), &original[spans[tail_start].0..spans[spans.len() - 1].1])
+}
+
+/// Strip one pair of matching surrounding quotes from an argument
+///
+/// Returns the inner text and whether quotes were removed.
+fn unquote(arg: &str) -> (&str, bool) {
+    for q in ['"', '\''] {
+        if arg.len() >= 2 && arg.starts_with(q) && arg.ends_with(q) {
+            return (&arg[1..arg.len() - 1], true);
+        }
+    }
+    (arg, false)
+}
+
+/// Detect shell constructs that can't be translated between shell families
+///
+/// Only applies when translating between cmd.exe and a Unix shell; Unix-like
+/// OSes share their shell syntax. Returns a description of the construct.
+fn detect_unsupported_construct(input: &str, from_os: Os, to_os: Os) -> Option<String> {
+    if from_os.is_unix_like() == to_os.is_unix_like() {
+        return None;
+    }
+
+    let trimmed = input.trim();
+    let first = trimmed.split_whitespace().next()?.to_lowercase();
+
+    if trimmed.starts_with('(') {
+        return Some("subshell".to_string());
+    }
+
+    if from_os == Os::Windows {
+        if matches!(first.as_str(), "if" | "for" | "goto") {
+            return Some(format!("control flow ('{}')", first));
+        }
+        return None;
+    }
+
+    if matches!(
+        first.as_str(),
+        "if" | "then" | "elif" | "else" | "fi" | "for" | "while" | "until" | "do" | "done"
+            | "case" | "esac" | "select" | "function" | "{" | "}"
+    ) {
+        return Some(format!("control flow ('{}')", first));
+    }
+
+    // findstr has no Perl regex dialect; only PowerShell's Select-String comes close
+    if first == "grep" && is_perl_regex_grep(&split_arguments(trimmed)) {
+        return Some("Perl regular expressions (grep -P); target PowerShell to use Select-String".to_string());
+    }
+
+    // Scan outside single quotes, where the shell would interpret these
+    let mut in_single_quote = false;
+    let mut prev = '\0';
+    for c in trimmed.chars() {
+        match c {
+            '\'' => in_single_quote = !in_single_quote,
+            '<' if !in_single_quote && prev == '<' => return Some("heredoc".to_string()),
+            '(' if !in_single_quote && prev == '$' => {
+                return Some("command substitution".to_string())
+            }
+            '`' if !in_single_quote => return Some("command substitution".to_string()),
+            _ => {}
+        }
+        prev = c;
+    }
+
+    None
+}
+
 /// Maximum length of a Windows-style flag (e.g., "/w", "/s", "/a:")
 /// Used to distinguish short flags from paths that start with "/"
 const MAX_WINDOWS_FLAG_LEN: usize = 4;
@@ -122,7 +440,7 @@ fn is_path_argument(arg: &str, from_os: Os) -> bool {
     false
 }
 
-/// Translate path arguments in a list of arguments
+/// Translate path arguments and environment variable references in a list of arguments
 fn translate_path_args(
     args: &[String], 
     from_os: Os, 
@@ -130,24 +448,118 @@ fn translate_path_args(
     result: &mut TranslationResult
 ) -> Vec<String> {
     args.iter().map(|arg| {
-        if is_path_argument(arg, from_os) {
-            match translate_path(arg, from_os, to_os) {
-                Ok(path_result) => {
-                    if !path_result.warnings.is_empty() {
-                        result.warnings.extend(path_result.warnings);
-                    }
-                    path_result.path
+        let arg = translate_path_arg(arg, from_os, to_os, result);
+        // Variables are converted last: path translation leaves them alone and
+        // each side's output syntax is never read as the other's input
+        let translated = translate_env_vars(&arg, from_os, to_os);
+        if to_os == Os::Windows && arg.starts_with('$') && translated != arg {
+            translated.replace('/', "\\")
+        } else {
+            translated
+        }
+    }).collect()
+}
+
+/// Translate one argument if it is a path
+fn translate_path_arg(arg: &str, from_os: Os, to_os: Os, result: &mut TranslationResult) -> String {
+    let (inner, quoted) = unquote(arg);
+    if is_path_argument(inner, from_os) {
+        match translate_path(inner, from_os, to_os) {
+            Ok(path_result) => {
+                if !path_result.warnings.is_empty() {
+                    result.warnings.extend(path_result.warnings);
                 }
-                Err(e) => {
-                    // Log path translation error as a warning for debugging
-                    result.warnings.push(format!("Path '{}' could not be translated: {}", arg, e));
-                    arg.clone() // Keep original if translation fails
+                // Quotes are only needed again if the path contains spaces
+                if quoted && path_result.path.contains(char::is_whitespace) {
+                    format!("\"{}\"", path_result.path)
+                } else {
+                    path_result.path
                 }
             }
-        } else {
-            arg.clone()
+            Err(e) => {
+                // Log path translation error as a warning for debugging
+                result.warnings.push(format!("Path '{}' could not be translated: {}", arg, e));
+                arg.to_string() // Keep original if translation fails
+            }
         }
-    }).collect()
+    } else {
+        arg.to_string()
+    }
+}
+
+/// An argument produced by [`translate_flags`]
+struct TranslatedArg {
+    text: String,
+    /// Produced by a flag mapping, as opposed to an operand or unmapped flag
+    is_flag: bool,
+}
+
+impl TranslatedArg {
+    fn flag(text: impl Into<String>) -> Self {
+        Self { text: text.into(), is_flag: true }
+    }
+
+    fn operand(text: impl Into<String>) -> Self {
+        Self { text: text.into(), is_flag: false }
+    }
+}
+
+/// Build the target command line: the mapped command followed by its translated flags
+///
+/// A flag can replace the target command itself, like `cp -r` becoming
+/// `xcopy /s /e /y` rather than `copy`; the first such flag wins.
+fn build_translated_command(
+    args: &[String],
+    mapping: &CommandMapping,
+    result: &mut TranslationResult,
+) -> String {
+    let to_os = result.to_os;
+    let is_command = |a: &TranslatedArg| a.is_flag && !looks_like_flag(&a.text) && is_native_command(&a.text, to_os);
+
+    let mut translated_args = translate_flag_args(args, mapping, result);
+    warn_about_wildcards(translated_args.iter().filter(|a| !a.is_flag).map(|a| a.text.as_str()), result);
+    let command = translated_args
+        .iter()
+        .find(|a| is_command(a))
+        .map_or_else(|| mapping.target_cmd.clone(), |a| a.text.clone());
+    translated_args.retain(|a| !is_command(a));
+
+    let mut final_command = command;
+    if !translated_args.is_empty() {
+        final_command.push(' ');
+        final_command.push_str(&translated_args.iter().map(|a| a.text.as_str()).collect::<Vec<_>>().join(" "));
+    }
+    final_command
+}
+
+/// Warn when an unquoted operand contains `*` or `?`
+///
+/// cmd.exe passes wildcards to the command, which matches them itself (and
+/// `*.*` matches every file), while Unix shells expand them before the
+/// command runs and `*.*` only matches names containing a dot. Only applies
+/// between shell families; URLs and variable references such as `$?` (from
+/// `%ERRORLEVEL%`) or `%*` are skipped.
+fn warn_about_wildcards<'a>(operands: impl Iterator<Item = &'a str>, result: &mut TranslationResult) {
+    if result.from_os.is_unix_like() == result.to_os.is_unix_like() {
+        return;
+    }
+    let is_glob = |operand: &&str| {
+        let unreferenced = operand.replace("$?", "").replace("$*", "").replace("%*", "");
+        !unquote(operand).1 && !operand.contains("://") && unreferenced.contains(['*', '?'])
+    };
+    let globs: Vec<&str> = operands.filter(is_glob).collect();
+    if globs.is_empty() {
+        return;
+    }
+
+    let mut warning = format!(
+        "Wildcards in '{}' are expanded by the shell on Unix but by the command itself on Windows; matches may differ (hidden files, no matches)",
+        globs.join("', '")
+    );
+    if globs.contains(&"*.*") {
+        warning.push_str("; '*.*' matches every file on Windows but only names containing a dot on Unix");
+    }
+    result.warnings.push(warning);
 }
 
 /// Translate flags from source to target OS
@@ -156,468 +568,1350 @@ fn translate_flags(
     mapping: &CommandMapping,
     result: &mut TranslationResult,
 ) -> Vec<String> {
-    let mut translated_args = Vec::new();
+    translate_flag_args(args, mapping, result).into_iter().map(|a| a.text).collect()
+}
+
+/// Translate flags from source to target OS, keeping track of which are flags
+///
+/// For Windows targets, translated `/` switches are emitted before the
+/// operands in their original relative order, since some cmd.exe tools
+/// treat `del file /s` differently from `del /s file`. Everything else
+/// keeps its position so flag values stay next to their flag.
+fn translate_flag_args(
+    args: &[String],
+    mapping: &CommandMapping,
+    result: &mut TranslationResult,
+) -> Vec<TranslatedArg> {
+    let mut translated_args: Vec<TranslatedArg> = Vec::new();
+    let mut redirect_target = false;
     
-    for arg in args {
-        let mut found = false;
+    for (i, arg) in args.iter().enumerate() {
+        // The word after a redirection operator is a file, never a flag
+        if redirect_target || is_redirect_operator(arg) {
+            redirect_target = !redirect_target;
+            translated_args.push(TranslatedArg::operand(arg.clone()));
+            continue;
+        }
+
+        // `--` ends option parsing: everything after it is a literal operand.
+        // cmd.exe has no such marker, so it is only kept for Unix targets.
+        if arg == "--" {
+            if result.to_os.is_unix_like() {
+                translated_args.push(TranslatedArg::operand(arg.clone()));
+            }
+            translated_args.extend(args[i + 1..].iter().cloned().map(TranslatedArg::operand));
+            break;
+        }
+
+        let ignore_case = result.from_os == Os::Windows;
+
+        // Handle exact match first so combined entries like `-rf` win over
+        // the value-carrying prefix match of `-r`
+        if let Some(flag_mapping) = mapping.find_flag(arg, ignore_case) {
+            // Handle cases where target contains multiple flags
+            for part in flag_mapping.target.split_whitespace() {
+                translated_args.push(TranslatedArg::flag(part));
+            }
+            continue;
+        }
         
-        // Check if this is a flag that needs translation
-        for flag_mapping in &mapping.flag_mappings {
-            // Handle exact match
-            if arg == &flag_mapping.source || arg.to_lowercase() == flag_mapping.source.to_lowercase() {
-                if !flag_mapping.target.is_empty() {
-                    // Handle cases where target contains multiple flags
-                    for part in flag_mapping.target.split_whitespace() {
-                        translated_args.push(part.to_string());
-                    }
+        // Bundled short flags (`-al`, `-lah`) are decomposed so every ordering
+        // gives the same result as the combined entries like `-la`
+        if let Some((parts, unknown)) = translate_bundled_flags(arg, mapping, result.from_os.is_unix_like()) {
+            for part in parts {
+                if !translated_args.iter().any(|a| a.text == part) {
+                    translated_args.push(TranslatedArg::flag(part));
                 }
-                found = true;
-                break;
             }
-            
-            // Handle flags with values (e.g., -n 5 or /n:5)
-            if arg.starts_with(&flag_mapping.source) {
-                let value = &arg[flag_mapping.source.len()..];
-                if !flag_mapping.target.is_empty() {
-                    if value.is_empty() {
-                        translated_args.push(flag_mapping.target.clone());
-                    } else {
-                        // Handle different flag value formats
-                        let value_clean = value.trim_start_matches(':').trim_start_matches('=');
-                        translated_args.push(format!("{} {}", flag_mapping.target, value_clean));
-                    }
+            for flag in unknown {
+                if mapping.preserve_unmapped_flags {
+                    result.warnings.push(format!("Flag '{}' (from '{}') was not translated", flag, arg));
+                    translated_args.push(TranslatedArg::operand(flag.clone()));
+                } else {
+                    result.warnings.push(format!("Flag '{}' (from '{}') was dropped", flag, arg));
                 }
-                found = true;
-                break;
+                result.had_unmapped_flags = true;
+                result.unmapped_flags.push(flag);
+            }
+            continue;
+        }
+
+        // Handle flags with values (e.g., -n5 or /n:5); the value keeps its case
+        let found = mapping.find_flag_prefix(arg, ignore_case);
+        if let Some((flag_mapping, value)) = found.filter(|(f, _)| !f.target.is_empty()) {
+            if value.is_empty() {
+                translated_args.push(TranslatedArg::flag(flag_mapping.target.clone()));
+            } else {
+                translated_args.push(TranslatedArg::flag(format!("{} {}", flag_mapping.target, value)));
             }
         }
         
         // If flag wasn't found in mappings
-        if !found {
+        if found.is_none() {
             if mapping.preserve_unmapped_flags {
                 // Keep the original arg
-                translated_args.push(arg.clone());
+                translated_args.push(TranslatedArg::operand(arg.clone()));
                 
-                // Warn about unmapped flags
-                if arg.starts_with('-') || arg.starts_with('/') {
+                // Warn about unmapped flags (Unix-like OSes share most flags).
+                // Multi-component paths such as translated `/mnt/c/x` aren't flags.
+                let compatible = result.from_os.is_unix_like() && result.to_os.is_unix_like();
+                let is_path = arg.starts_with('/') && arg[1..].contains('/');
+                if !compatible && !is_path && (arg.starts_with('-') || arg.starts_with('/')) {
                     result.warnings.push(format!("Flag '{}' was not translated", arg));
                     result.had_unmapped_flags = true;
+                    result.unmapped_flags.push(arg.clone());
                 }
             } else {
                 result.warnings.push(format!("Flag '{}' was dropped", arg));
                 result.had_unmapped_flags = true;
+                result.unmapped_flags.push(arg.clone());
             }
         }
     }
-    
+
+    // Flags repeated between operands (`rm a -f b -f c`), or mapped to the same
+    // target (`del /f /q`), are emitted once
+    let mut seen = HashSet::new();
+    translated_args.retain(|a| !a.is_flag || seen.insert(a.text.clone()));
+
+    if result.to_os == Os::Windows {
+        // Stable sort: switches move ahead, everything else keeps its order
+        translated_args.sort_by_key(|a| !(a.is_flag && a.text.starts_with('/')));
+    }
     translated_args
 }
 
-/// Translate a command from one OS to another
-///
-/// # Arguments
+/// Check for a standalone redirection operator such as `>`, `2>>` or `<`
+fn is_redirect_operator(arg: &str) -> bool {
+    let op = arg.strip_prefix(|c: char| c.is_ascii_digit() || c == '&').unwrap_or(arg);
+    matches!(op, ">" | ">>" | "<")
+}
+
+/// Translate a bundle of single-letter Unix flags (`-lah`) flag by flag
 ///
-/// * `input` - The command string to translate
-/// * `from_os` - The source operating system
-/// * `to_os` - The target operating system
+/// Applies when every letter has an exact mapping. With `split_partial`,
+/// all-letter bundles where only some letters are mapped are split too, and
+/// the unmapped letters are returned as separate flags (`-laX` gives the
+/// targets of `-l` and `-a`, plus `-X`). Otherwise `None` is returned so
+/// value-carrying flags like `-n5` keep their prefix handling. Duplicate and
+/// empty targets are dropped.
+fn translate_bundled_flags(
+    arg: &str,
+    mapping: &CommandMapping,
+    split_partial: bool,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let letters = arg.strip_prefix('-')?;
+    if letters.len() < 2 || !letters.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut unknown: Vec<String> = Vec::new();
+    for letter in letters.chars() {
+        let flag = format!("-{}", letter);
+        let Some(flag_mapping) = mapping.flag_mappings.iter().find(|f| f.source == flag) else {
+            unknown.push(flag);
+            continue;
+        };
+        for part in flag_mapping.target.split_whitespace() {
+            if !parts.iter().any(|p| p == part) {
+                parts.push(part.to_string());
+            }
+        }
+    }
+
+    let partial_ok = split_partial && is_short_flag_bundle(arg) && unknown.len() < letters.len();
+    (unknown.is_empty() || partial_ok).then_some((parts, unknown))
+}
+
+/// Translate commands whose behavior differs in ways a flag table can't express
 ///
-/// # Returns
+/// Returns `Some(command)` when the command was handled here, `None` to fall
+/// back to the regular mapping lookup. When `translate_paths` is set, path
+/// operands are translated as in [`translate_full`].
+fn translate_special_command(
+    command_name: &str,
+    args: &[String],
+    from_os: Os,
+    to_os: Os,
+    translate_paths: bool,
+    result: &mut TranslationResult,
+) -> Option<String> {
+    let operands = |args: &[String], result: &mut TranslationResult| {
+        if translate_paths {
+            translate_path_args(args, from_os, to_os, result)
+        } else {
+            args.to_vec()
+        }
+    };
+
+    match command_name {
+        name if from_os == Os::Windows && to_os.is_unix_like() && name.trim_start_matches('@').starts_with("echo") => {
+            translate_batch_echo(name, args, result)
+        }
+        "echo" if from_os.is_unix_like() && to_os == Os::Windows => {
+            let args = operands(args, result);
+            Some(translate_echo_to_windows(&args, result))
+        }
+        "start" if from_os == Os::Windows && to_os.is_unix_like() => {
+            let mapping = get_mapping("start", from_os, to_os)?;
+            let args = operands(args, result);
+            Some(translate_start_to_unix(&args, mapping, result))
+        }
+        "grep" if from_os.is_unix_like() && to_os == Os::Windows => {
+            let mapping = get_mapping("grep", from_os, to_os)?;
+            let args = operands(args, result);
+            Some(translate_grep_to_windows(&args, mapping, result))
+        }
+        "powershell" | "pwsh" if from_os == Os::Windows && to_os.is_unix_like() => {
+            Some(translate_powershell_wrapper(args, from_os, to_os, result))
+        }
+        "cp" if from_os.is_unix_like() && to_os == Os::Windows && args.iter().any(|a| is_archive_flag(a)) => {
+            let args = operands(args, result);
+            translate_cp_archive_to_windows(&args, result)
+        }
+        "taskkill" if from_os == Os::Windows && to_os.is_unix_like() => {
+            Some(translate_taskkill_to_unix(args, result))
+        }
+        "route" if from_os == Os::Windows => {
+            let mapping = get_mapping("route", from_os, to_os)?;
+            Some(translate_route_to_ip(args, mapping, result))
+        }
+        "netsh" if from_os == Os::Windows => {
+            let mapping = get_mapping("netsh", from_os, to_os)?;
+            Some(translate_netsh_to_ip(args, mapping, result))
+        }
+        // A script run from the current directory (`./run.sh`) runs its batch port
+        name if from_os.is_unix_like() && to_os == Os::Windows && name.starts_with("./") && name.ends_with(".sh") => {
+            let mut args = operands(args, result);
+            args.insert(0, translate_script_extension(&name[2..], from_os, to_os));
+            Some(format!("call {}", args.join(" ")))
+        }
+        "source" | "." if from_os.is_unix_like() && to_os == Os::Windows => {
+            let args = translate_sourced_script(args, "./", from_os, to_os)?;
+            Some(format!("call {}", operands(&args, result).join(" ")))
+        }
+        // `call :label` jumps to a subroutine, which has no shell equivalent
+        "call" if from_os == Os::Windows && to_os.is_unix_like() && !args.first()?.starts_with(':') => {
+            let args = translate_sourced_script(args, ".\\", from_os, to_os)?;
+            Some(format!("source {}", operands(&args, result).join(" ")))
+        }
+        "sed" if matches!((from_os, to_os), (Os::Linux, Os::MacOS) | (Os::MacOS, Os::Linux)) => {
+            translate_sed_in_place(args, to_os, result)
+        }
+        "stdbuf" if from_os.is_unix_like() && to_os == Os::Windows => {
+            Some(translate_stdbuf_to_windows(args, from_os, to_os, translate_paths, result))
+        }
+        "chmod" if from_os.is_unix_like() && to_os == Os::Windows => {
+            let args = operands(args, result);
+            Some(translate_chmod_to_windows(&args, result))
+        }
+        "export" if from_os.is_unix_like() && to_os == Os::Windows => {
+            translate_path_assignment(args, from_os, to_os, result)
+                .or_else(|| translate_variable_assignment(args, from_os, to_os, result))
+        }
+        "set" if from_os == Os::Windows && to_os.is_unix_like() => {
+            translate_path_assignment(args, from_os, to_os, result)
+                .or_else(|| translate_variable_assignment(args, from_os, to_os, result))
+        }
+        "cd" | "chdir" | "pushd" | "popd" if from_os.is_unix_like() != to_os.is_unix_like() => {
+            // cmd allows unquoted spaces in the directory, so it is one operand
+            let dir: Vec<String> = args
+                .iter()
+                .filter(|a| !(from_os == Os::Windows && a.eq_ignore_ascii_case("/d")))
+                .cloned()
+                .collect();
+            if command_name == "pushd" && from_os == Os::Windows && dir.first().is_some_and(|d| unquote(d).0.starts_with("\\\\")) {
+                result.warnings.push(
+                    "pushd to a UNC path maps a temporary drive on Windows; on Unix the share must already be mounted".to_string(),
+                );
+            }
+            // pushd's operand is always a directory, so it is translated as a path
+            let dir = if dir.is_empty() {
+                dir
+            } else if command_name == "pushd" {
+                translate_path_args(&[dir.join(" ")], from_os, to_os, result)
+            } else {
+                operands(&[dir.join(" ")], result)
+            };
+            Some(translate_change_directory(command_name, dir.first().map(String::as_str), to_os, result))
+        }
+        _ => None,
+    }
+}
+
+/// Translate `cd`/`chdir`/`pushd`/`popd` between cmd.exe and Unix shells
 ///
-/// * `Ok(TranslationResult)` - The translated command
-/// * `Err(TranslationError)` - Error if translation fails
+/// cmd's `cd /d` also switches drive; Unix has no drives so `/d` is dropped,
+/// and added back for Windows targets when the directory has a drive letter.
+/// A bare `cd` prints the current directory in cmd but goes home in bash.
+fn translate_change_directory(
+    command_name: &str,
+    dir: Option<&str>,
+    to_os: Os,
+    result: &mut TranslationResult,
+) -> String {
+    let command = if command_name == "chdir" { "cd" } else { command_name };
+
+    match (command, dir) {
+        ("popd", _) => "popd".to_string(),
+        ("cd", None) if to_os == Os::Windows => "cd /d %USERPROFILE%".to_string(),
+        ("cd", None) => "pwd".to_string(),
+        ("cd", Some("-")) if to_os == Os::Windows => {
+            result.warnings.push("cmd.exe has no 'cd -'; use pushd/popd to return to a directory".to_string());
+            "cd -".to_string()
+        }
+        ("cd", Some(dir)) if to_os == Os::Windows => {
+            let bytes = unquote(dir).0.as_bytes();
+            if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+                format!("cd /d {}", dir)
+            } else {
+                format!("cd {}", dir)
+            }
+        }
+        (command, Some(dir)) if to_os != Os::Windows && !unquote(dir).1 && dir.contains(' ') => {
+            format!("{} \"{}\"", command, dir)
+        }
+        (command, Some(dir)) => format!("{} {}", command, dir),
+        (command, None) => command.to_string(),
+    }
+}
+
+/// Translate a Unix `echo` invocation to cmd.exe
 ///
-/// # Example
+/// cmd's `echo` has no options: `-e` is dropped since escapes are never
+/// interpreted, and `-n` uses the `echo | set /p=` trick to suppress the newline.
+fn translate_echo_to_windows(args: &[String], result: &mut TranslationResult) -> String {
+    let mut no_newline = false;
+    let mut operand_start = 0;
+
+    // Leading option bundles like -n, -e, -ne (any other token ends option parsing)
+    for arg in args {
+        let is_option = arg.len() > 1
+            && arg.starts_with('-')
+            && arg[1..].chars().all(|c| matches!(c, 'n' | 'e' | 'E'));
+        if !is_option {
+            break;
+        }
+        if arg.contains('n') {
+            no_newline = true;
+        }
+        if arg.contains('e') {
+            result.warnings.push(
+                "Flag '-e' was dropped: cmd echo does not interpret escape sequences".to_string(),
+            );
+        }
+        operand_start += 1;
+    }
+
+    // An empty argument is kept so the line isn't mistaken for a bare `echo`;
+    // cmd only knows double quotes
+    let text = args[operand_start..]
+        .iter()
+        .map(|arg| if arg == "''" { "\"\"" } else { arg.as_str() })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if no_newline {
+        result.warnings.push(
+            "echo -n translated to 'echo | set /p=' to suppress the trailing newline".to_string(),
+        );
+        return format!("echo | set /p={}", text);
+    }
+
+    // A bare `echo` on cmd prints the echo state instead of a blank line
+    if text.is_empty() {
+        return "echo.".to_string();
+    }
+
+    format!("echo {}", text)
+}
+
+/// Translate a Windows `start` invocation to an opener such as `xdg-open`
 ///
-/// ```
-/// use cmdx::{translate_command, Os};
+/// `start` treats a leading quoted argument as the window title, so
+/// `start "" file` must open `file`, not the empty string.
+fn translate_start_to_unix(
+    args: &[String],
+    mapping: &CommandMapping,
+    result: &mut TranslationResult,
+) -> String {
+    let mut rest = args;
+    if let Some(first) = rest.first() {
+        if first.starts_with('"') {
+            if !unquote(first).0.is_empty() {
+                result.warnings.push(format!("Window title {} was dropped", first));
+            }
+            rest = &rest[1..];
+        }
+    }
+
+    let mut command = mapping.target_cmd.clone();
+    if !rest.is_empty() {
+        command.push(' ');
+        command.push_str(&rest.join(" "));
+    }
+    command
+}
+
+/// Translate `powershell -Command "..."` for a Unix target
 ///
-/// let result = translate_command("dir /w", Os::Windows, Os::Linux);
-/// assert!(result.is_ok());
-/// println!("{}", result.unwrap());
-/// ```
-pub fn translate_command(
-    input: &str,
+/// PowerShell on Unix is `pwsh`, so the wrapper is renamed and the inner
+/// command is translated through the cmdlet mappings. Other options such as
+/// `-NoProfile` are kept; inner commands that can't be translated are left as
+/// written, since pwsh still understands them.
+fn translate_powershell_wrapper(
+    args: &[String],
     from_os: Os,
     to_os: Os,
-) -> Result<TranslationResult, TranslationError> {
-    // Check for empty input
-    let trimmed = input.trim();
-    if trimmed.is_empty() {
-        return Err(TranslationError::EmptyCommand);
+    result: &mut TranslationResult,
+) -> String {
+    let mut parts = vec!["pwsh".to_string()];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        parts.push(arg.clone());
+        if !matches!(arg.to_lowercase().as_str(), "-command" | "-c") {
+            continue;
+        }
+        let Some(script) = iter.next() else { break };
+        let (inner, quoted) = unquote(script);
+        let quote = if quoted { &script[..1] } else { "" };
+        let translated = match translate_compound_command(inner, from_os, to_os) {
+            Ok(translated) => {
+                result.warnings.extend(translated.warnings);
+                result.had_unmapped_flags |= translated.had_unmapped_flags;
+                result.unmapped_flags.extend(translated.unmapped_flags);
+                translated.command
+            }
+            Err(_) => {
+                result.warnings.push(format!(
+                    "PowerShell command '{}' was not translated",
+                    inner.split_whitespace().next().unwrap_or(inner)
+                ));
+                inner.to_string()
+            }
+        };
+        parts.push(format!("{}{}{}", quote, translated, quote));
     }
-    
-    // Same OS - just return the input
-    if from_os == to_os {
-        return Ok(TranslationResult::new(
-            trimmed.to_string(),
-            trimmed.to_string(),
-            from_os,
-            to_os,
-        ));
+    parts.join(" ")
+}
+
+/// Translate `taskkill` to `kill` for `/pid` and `pkill` for `/im`
+///
+/// Image names lose their `.exe` suffix, since Unix process names have none.
+/// `/f` becomes signal 9; other switches are dropped with a warning.
+fn translate_taskkill_to_unix(args: &[String], result: &mut TranslationResult) -> String {
+    let mut force = false;
+    let mut pids = Vec::new();
+    let mut images = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (switch, value) = match arg.split_once(':') {
+            Some((switch, value)) => (switch.to_lowercase(), Some(value)),
+            None => (arg.to_lowercase(), None),
+        };
+        match switch.as_str() {
+            "/f" => force = true,
+            "/pid" => pids.extend(value.map(str::to_string).or_else(|| iter.next().cloned())),
+            "/im" => images.extend(value.map(str::to_string).or_else(|| iter.next().cloned())),
+            "/t" => result.warnings.push(
+                "taskkill /t has no kill equivalent; child processes are not killed".to_string(),
+            ),
+            _ if arg.starts_with('/') => {
+                // Switches like /s and /u take a value
+                if matches!(switch.as_str(), "/s" | "/u" | "/p" | "/fi") && value.is_none() {
+                    iter.next();
+                }
+                result.warnings.push(format!("Flag '{}' was not translated", arg));
+                result.had_unmapped_flags = true;
+                result.unmapped_flags.push(arg.clone());
+            }
+            _ => pids.push(arg.clone()),
+        }
     }
-    
-    // Parse the command
-    let (command_name, args) = parse_command(trimmed);
-    
-    if command_name.is_empty() {
-        return Err(TranslationError::EmptyCommand);
+
+    let signal = if force { " -9" } else { "" };
+    let mut commands = Vec::new();
+    if !pids.is_empty() {
+        commands.push(format!("kill{} {}", signal, pids.join(" ")));
     }
-    
-    // Check if the command is already native to the target OS
-    // If so, pass it through without transformation
-    if is_native_command(&command_name, to_os) && !is_native_command(&command_name, from_os) {
-        // Command is already in target OS format, pass through
-        let mut result = TranslationResult::new(
-            trimmed.to_string(),
-            trimmed.to_string(),
-            from_os,
-            to_os,
-        );
-        result.warnings.push(format!(
-            "Command '{}' is already in {} format, passed through unchanged",
-            command_name, to_os
-        ));
-        return Ok(result);
+    for image in &images {
+        let (name, quoted) = unquote(image);
+        let name = match name.len().checked_sub(4) {
+            Some(base) if name.is_char_boundary(base) && name[base..].eq_ignore_ascii_case(".exe") => &name[..base],
+            _ => name,
+        };
+        let name = if quoted { format!("\"{}\"", name) } else { name.to_string() };
+        commands.push(format!("pkill{} {}", signal, name));
     }
-    
-    // Check if the command is native to the target OS (same command on both)
-    // For example, 'ping' exists on both Windows and Linux
-    if is_native_command(&command_name, to_os) && is_native_command(&command_name, from_os) {
-        // Command exists on both OSes - check if we have flag translations
-        if let Some(mapping) = get_mapping(&command_name, from_os, to_os) {
-            // We have flag mappings, so translate the flags
-            let mut result = TranslationResult::new(
-                String::new(),
-                trimmed.to_string(),
-                from_os,
-                to_os,
-            );
-            
-            let translated_args = translate_flags(&args, mapping, &mut result);
-            
-            let mut final_command = mapping.target_cmd.clone();
-            if !translated_args.is_empty() {
-                final_command.push(' ');
-                final_command.push_str(&translated_args.join(" "));
-            }
-            
-            result.command = final_command;
-            return Ok(result);
-        } else {
-            // No flag mappings, pass through unchanged
-            return Ok(TranslationResult::new(
-                trimmed.to_string(),
-                trimmed.to_string(),
-                from_os,
-                to_os,
-            ));
-        }
+
+    if commands.is_empty() {
+        "kill".to_string()
+    } else {
+        commands.join(" ; ")
     }
-    
-    // Look up the mapping
-    let mapping = match get_mapping(&command_name, from_os, to_os) {
-        Some(m) => m,
-        None => {
-            // Try to find a generic Unix-like mapping if both are Unix-like
-            if from_os.is_unix_like() && to_os.is_unix_like() {
-                // Unix commands are generally compatible
-                let mut result = TranslationResult::new(
-                    trimmed.to_string(),
-                    trimmed.to_string(),
-                    from_os,
-                    to_os,
-                );
+}
+
+/// Translate cmd.exe `echo` idioms that a shell `echo` doesn't share
+///
+/// `echo.` (or `echo,`, `echo:` and the like) prints a blank line, or the
+/// text glued to the separator, and becomes `echo ""` / `echo text`.
+/// `echo off` and `echo on` only switch command echoing, which shells don't
+/// do by default, so they become the no-op `:`. A leading `@` is dropped.
+/// Returns `None` for a plain `echo`, which the mapping handles.
+fn translate_batch_echo(name: &str, args: &[String], result: &mut TranslationResult) -> Option<String> {
+    let silenced = name.starts_with('@');
+    let rest = name.trim_start_matches('@').strip_prefix("echo")?;
+    // The command name arrives lowercased; text glued to it keeps its case
+    let rest = result
+        .original
+        .split_whitespace()
+        .next()
+        .filter(|word| word.eq_ignore_ascii_case(name))
+        .map_or(rest, |word| &word[word.len() - rest.len()..]);
+
+    let Some(text) = rest.strip_prefix(['.', ',', ':', ';', '/', '(', '=', '+', '[', ']']) else {
+        if !rest.is_empty() {
+            return None;
+        }
+        if let [state] = args {
+            if state.eq_ignore_ascii_case("off") || state.eq_ignore_ascii_case("on") {
                 result.warnings.push(format!(
-                    "Command '{}' passed through (Unix-like OS compatibility assumed)",
-                    command_name
+                    "echo {} only switches command echoing in cmd.exe; replaced with a no-op",
+                    state.to_lowercase()
                 ));
-                return Ok(result);
+                return Some(":".to_string());
             }
-            
-            // Check if command is already a target OS command
-            if is_target_command_for_os(&command_name, to_os) {
-                let mut result = TranslationResult::new(
-                    trimmed.to_string(),
-                    trimmed.to_string(),
-                    from_os,
-                    to_os,
+        }
+        // `@echo text` is a plain echo once the `@` is gone
+        return silenced.then(|| {
+            let mut words = vec!["echo".to_string()];
+            words.extend(args.iter().cloned());
+            words.join(" ")
+        });
+    };
+
+    // The separator itself isn't printed; anything after it is
+    let mut words: Vec<&str> = Vec::new();
+    if !text.is_empty() {
+        words.push(text);
+    }
+    words.extend(args.iter().map(String::as_str));
+    if words.is_empty() {
+        Some("echo \"\"".to_string())
+    } else {
+        Some(format!("echo {}", words.join(" ")))
+    }
+}
+
+/// Translate `route print/add/delete/change` to `ip route`
+///
+/// `mask` netmasks are folded into the destination as a CIDR prefix, the
+/// gateway becomes `via` and `metric` is kept. Interface indexes and
+/// persistent routes have no `ip route` form and are dropped with a warning.
+fn translate_route_to_ip(args: &[String], mapping: &CommandMapping, result: &mut TranslationResult) -> String {
+    let mut family = "";
+    let mut subcommand = None;
+    let mut operands = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.to_lowercase().as_str() {
+            "-4" | "-6" => family = if arg == "-4" { " -4" } else { " -6" },
+            "-p" => result.warnings.push(
+                "route -p has no ip route equivalent; add persistent routes to the network configuration".to_string(),
+            ),
+            "-f" => result.warnings.push(
+                "route -f (clear all gateway routes) was not translated; use 'ip route flush' deliberately".to_string(),
+            ),
+            "print" | "add" | "delete" | "change" if subcommand.is_none() => {
+                subcommand = mapping.find_flag(arg, true).map(|f| f.target.clone());
+            }
+            "mask" => match iter.next().map(|m| netmask_prefix_len(m)) {
+                Some(Some(prefix)) => {
+                    if let Some(destination) = operands.first_mut() {
+                        *destination = format!("{}/{}", destination, prefix);
+                    }
+                }
+                _ => result.warnings.push("route mask is not a valid netmask and was dropped".to_string()),
+            },
+            "metric" => operands.extend(iter.next().map(|m| format!("metric {}", m))),
+            "if" => {
+                iter.next();
+                result.warnings.push(
+                    "route interface index (if) was not translated; add 'dev <name>' for the interface".to_string(),
                 );
-                result.warnings.push(format!(
-                    "Command '{}' appears to already be a {} command, passed through unchanged",
-                    command_name, to_os
-                ));
-                return Ok(result);
             }
-            
-            return Err(TranslationError::CommandNotFound(command_name));
+            _ => operands.push(arg.clone()),
+        }
+    }
+
+    let subcommand = subcommand.unwrap_or_else(|| "show".to_string());
+    if subcommand == "show" {
+        if !operands.is_empty() {
+            result.warnings.push("route print filters were not translated; the whole table is shown".to_string());
+        }
+        return format!("ip{} route show", family);
+    }
+
+    // Destination, then an optional gateway, then options like metric
+    let mut parts = vec![format!("ip{} route {}", family, subcommand)];
+    let mut operands = operands.into_iter();
+    if let Some(destination) = operands.next() {
+        parts.push(if destination == "0.0.0.0/0" { "default".to_string() } else { destination });
+    }
+    for operand in operands {
+        if operand.starts_with("metric ") || subcommand == "del" {
+            parts.push(operand);
+        } else {
+            parts.push(format!("via {}", operand));
         }
-    };
-    
-    // Create result
-    let mut result = TranslationResult::new(
-        String::new(),
-        trimmed.to_string(),
-        from_os,
-        to_os,
-    );
-    
-    // Translate flags
-    let translated_args = translate_flags(&args, mapping, &mut result);
-    
-    // Build the final command
-    let mut final_command = mapping.target_cmd.clone();
-    
-    if !translated_args.is_empty() {
-        final_command.push(' ');
-        final_command.push_str(&translated_args.join(" "));
     }
-    
-    result.command = final_command;
-    
-    // Add notes from mapping if any
     if let Some(notes) = &mapping.notes {
         result.warnings.push(notes.clone());
     }
-    
-    Ok(result)
+    parts.join(" ")
 }
 
-/// Translate a command with string OS names
-pub fn translate_command_str(
-    input: &str,
-    from_os: &str,
-    to_os: &str,
-) -> Result<TranslationResult, TranslationError> {
-    let from = Os::parse(from_os)
-        .ok_or_else(|| TranslationError::InvalidOs(from_os.to_string()))?;
-    let to = Os::parse(to_os)
-        .ok_or_else(|| TranslationError::InvalidOs(to_os.to_string()))?;
-    
-    translate_command(input, from, to)
+/// Prefix length of a dotted IPv4 netmask like `255.255.255.0`
+fn netmask_prefix_len(mask: &str) -> Option<u32> {
+    let octets: Vec<u8> = mask.split('.').map(|o| o.parse().ok()).collect::<Option<_>>()?;
+    let bits = u32::from_be_bytes(octets.try_into().ok()?);
+    // A valid mask is a run of ones followed only by zeros
+    (bits.leading_ones() + bits.trailing_zeros() == 32 || bits == 0).then_some(bits.leading_ones())
 }
 
-/// Batch translate multiple commands
-pub fn translate_batch(
-    commands: &[&str],
-    from_os: Os,
-    to_os: Os,
-) -> Vec<Result<TranslationResult, TranslationError>> {
-    commands
-        .iter()
-        .map(|cmd| translate_command(cmd, from_os, to_os))
-        .collect()
+/// Translate `netsh interface ip set address` to `ip addr` and `ip route`
+///
+/// A static address becomes `ip addr replace` plus a default route for the
+/// gateway, and `dhcp` becomes `dhclient`. Other netsh contexts have no
+/// equivalent and fall back to showing addresses, with a warning.
+fn translate_netsh_to_ip(args: &[String], mapping: &CommandMapping, result: &mut TranslationResult) -> String {
+    if let Some(notes) = &mapping.notes {
+        result.warnings.push(notes.clone());
+    }
+
+    let words: Vec<String> = args.iter().map(|a| a.to_lowercase()).collect();
+    let is_set_address = words.len() >= 4
+        && matches!(words[0].as_str(), "interface" | "int")
+        && matches!(words[1].as_str(), "ip" | "ipv4")
+        && words[2] == "set"
+        && matches!(words[3].as_str(), "address" | "addr");
+    if args.is_empty() {
+        result.warnings.push("netsh without a context was not translated; showing addresses with ip addr instead".to_string());
+        result.had_unmapped_flags = true;
+        return mapping.target_cmd.clone();
+    }
+    if !is_set_address {
+        result.warnings.push(format!("netsh '{}' was not translated; showing addresses with ip addr instead", args.join(" ")));
+        result.had_unmapped_flags = true;
+        return mapping.target_cmd.clone();
+    }
+
+    // Parameters are positional or name=value: name, source, address, mask, gateway
+    let mut named = std::collections::HashMap::new();
+    for arg in &args[4..] {
+        let (key, value) = match arg.split_once('=') {
+            Some((key, value)) => (key.to_lowercase(), value),
+            None => {
+                let next = ["name", "source", "address", "mask", "gateway"]
+                    .into_iter()
+                    .find(|name| !named.contains_key(*name));
+                match next {
+                    Some(name) => (name.to_string(), arg.as_str()),
+                    None => continue,
+                }
+            }
+        };
+        named.insert(key, unquote(value).0.to_string());
+    }
+
+    let device = named.get("name").cloned().unwrap_or_else(|| "<interface>".to_string());
+    let device = if device.contains(' ') { format!("\"{}\"", device) } else { device };
+    result.warnings.push("Windows interface names differ from Linux device names (e.g. eth0); check the dev argument".to_string());
+
+    if named.get("source").is_some_and(|s| s.eq_ignore_ascii_case("dhcp")) {
+        return format!("dhclient {}", device);
+    }
+
+    let address = named.get("address").cloned().unwrap_or_default();
+    let address = match named.get("mask").and_then(|m| netmask_prefix_len(m)) {
+        Some(prefix) => format!("{}/{}", address, prefix),
+        None => address,
+    };
+    let mut command = format!("ip addr replace {} dev {}", address, device);
+    if let Some(gateway) = named.get("gateway").filter(|g| !g.eq_ignore_ascii_case("none")) {
+        command.push_str(&format!(" && ip route replace default via {}", gateway));
+    }
+    command
 }
 
-/// Translate a command with full path translation
-///
-/// This function translates both the command and any file paths in the arguments.
-/// It combines command translation with path translation for complete cross-platform conversion.
-///
-/// # Arguments
-///
-/// * `input` - The command string to translate (may include file paths)
-/// * `from_os` - The source operating system
-/// * `to_os` - The target operating system
-///
-/// # Returns
-///
-/// * `Ok(TranslationResult)` - The translated command with paths converted
-/// * `Err(TranslationError)` - Error if translation fails
+/// Give the script run by `source`/`call` the target's script extension
 ///
-/// # Example
+/// A leading `./` (or `.\`) is dropped, since `call` searches the current
+/// directory anyway. Arguments after the script are kept as they are.
+fn translate_sourced_script(args: &[String], current_dir: &str, from_os: Os, to_os: Os) -> Option<Vec<String>> {
+    let (script, rest) = args.split_first()?;
+    let script = script.strip_prefix(current_dir).unwrap_or(script);
+    let mut args = vec![translate_script_extension(script, from_os, to_os)];
+    args.extend_from_slice(rest);
+    Some(args)
+}
+
+/// Rewrite `sed -i` between GNU (Linux) and BSD (macOS) syntax
 ///
-/// ```
-/// use cmdx::{translate_full, Os};
+/// GNU takes an optional suffix glued to the flag (`-i`, `-i.bak`), while BSD
+/// always reads the next argument as the suffix (`-i ''`, `-i .bak`). Returns
+/// `None` when there's no in-place flag, leaving the rest to the flag table.
+fn translate_sed_in_place(args: &[String], to_os: Os, result: &mut TranslationResult) -> Option<String> {
+    let is_in_place = |a: &str| a.starts_with("-i") || a.starts_with("--in-place");
+    if !args.iter().any(|a| is_in_place(a)) {
+        return None;
+    }
+
+    let mut translated = vec!["sed".to_string()];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !is_in_place(arg) {
+            translated.push(if arg == "-r" || arg == "--regexp-extended" { "-E".to_string() } else { arg.clone() });
+            continue;
+        }
+        let suffix = arg
+            .strip_prefix("--in-place")
+            .map(|s| s.trim_start_matches('='))
+            .unwrap_or_else(|| &arg[2..]);
+        if to_os == Os::MacOS {
+            let suffix = if suffix.is_empty() { "''" } else { suffix };
+            translated.push(format!("-i {}", suffix));
+        } else {
+            // BSD's suffix is the next argument; an empty one means no backup
+            let suffix = if suffix.is_empty() { iter.next().map(String::as_str).unwrap_or("") } else { suffix };
+            translated.push(format!("-i{}", unquote(suffix).0));
+        }
+    }
+
+    result.warnings.push(if to_os == Os::MacOS {
+        "BSD sed -i takes the backup suffix as a separate argument ('' for none)".to_string()
+    } else {
+        "BSD sed -i suffix argument merged into GNU -i".to_string()
+    });
+    Some(translated.join(" "))
+}
+
+/// Check for `cp -a`/`--archive`, alone or in a bundle like `-av`
+fn is_archive_flag(arg: &str) -> bool {
+    arg == "--archive"
+        || arg
+            .strip_prefix('-')
+            .is_some_and(|l| !l.starts_with('-') && l.contains('a') && l.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// Translate `cp -a src dst` to robocopy, which can keep attributes and timestamps
 ///
-/// // Windows command with path to Linux
-/// let result = translate_full("copy C:\\Users\\file.txt D:\\backup\\", Os::Windows, Os::Linux);
-/// assert!(result.is_ok());
-/// let r = result.unwrap();
-/// assert!(r.command.contains("cp"));
-/// assert!(r.command.contains("/mnt/c/"));
+/// Only the two-operand form is handled; `None` falls back to the flag table.
+fn translate_cp_archive_to_windows(args: &[String], result: &mut TranslationResult) -> Option<String> {
+    let operands: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).collect();
+    let [source, destination] = operands[..] else { return None };
+
+    result.warnings.push(
+        "cp -a translated to robocopy /copyall; ownership and auditing info need admin rights, and Windows ACLs replace Unix permissions".to_string(),
+    );
+    Some(format!("robocopy {} {} /e /copyall /dcopy:t", source, destination))
+}
+
+/// Drop a `stdbuf` prefix and translate the wrapped command
 ///
-/// // Linux command with path to Windows  
-/// let result = translate_full("cp /home/user/file.txt /tmp/", Os::Linux, Os::Windows);
-/// assert!(result.is_ok());
-/// ```
-pub fn translate_full(
-    input: &str,
+/// Windows has no way to change a program's stdio buffering, so the mode
+/// options are discarded with a warning.
+fn translate_stdbuf_to_windows(
+    args: &[String],
     from_os: Os,
     to_os: Os,
-) -> Result<TranslationResult, TranslationError> {
-    // Check for empty input
-    let trimmed = input.trim();
-    if trimmed.is_empty() {
-        return Err(TranslationError::EmptyCommand);
+    translate_paths: bool,
+    result: &mut TranslationResult,
+) -> String {
+    let mut rest = args;
+    while let Some(arg) = rest.first() {
+        if matches!(arg.as_str(), "-i" | "-o" | "-e") {
+            rest = rest.get(2..).unwrap_or_default();
+        } else if arg.starts_with('-') {
+            rest = &rest[1..];
+        } else {
+            break;
+        }
     }
-    
-    // Same OS - just return the input
-    if from_os == to_os {
-        return Ok(TranslationResult::new(
-            trimmed.to_string(),
-            trimmed.to_string(),
-            from_os,
-            to_os,
-        ));
-    }
-    
-    // Parse the command
-    let (command_name, args) = parse_command(trimmed);
-    
-    if command_name.is_empty() {
-        return Err(TranslationError::EmptyCommand);
+    if rest.is_empty() {
+        result.warnings.push("stdbuf without a command to run was not translated".to_string());
+        result.confidence = Confidence::Passthrough;
+        return std::iter::once("stdbuf").chain(args.iter().map(String::as_str)).collect::<Vec<_>>().join(" ");
     }
-    
-    // First translate the paths in arguments
-    let mut result = TranslationResult::new(
-        String::new(),
-        trimmed.to_string(),
-        from_os,
-        to_os,
+    result.warnings.push(
+        "stdbuf has no Windows equivalent and was dropped; output may be buffered".to_string(),
     );
-    
-    let args_with_translated_paths = translate_path_args(&args, from_os, to_os, &mut result);
-    
-    // Check if the command is already native to the target OS
-    if is_native_command(&command_name, to_os) && !is_native_command(&command_name, from_os) {
-        // Command is already in target OS format, just use translated paths
-        let mut final_command = command_name.clone();
-        if !args_with_translated_paths.is_empty() {
-            final_command.push(' ');
-            final_command.push_str(&args_with_translated_paths.join(" "));
+
+    let inner = rest.join(" ");
+    let translated = if translate_paths {
+        translate_full(&inner, from_os, to_os)
+    } else {
+        translate_command(&inner, from_os, to_os)
+    };
+    match translated {
+        Ok(translated) => {
+            result.warnings.extend(translated.warnings);
+            result.had_unmapped_flags |= translated.had_unmapped_flags;
+            result.unmapped_flags.extend(translated.unmapped_flags);
+            translated.command
         }
-        result.command = final_command;
+        Err(_) => {
+            result.warnings.push(format!("Command '{}' was not translated", rest[0]));
+            inner
+        }
+    }
+}
+
+/// Split a grep short-flag argument that carries a count (`-m`, `-A`, `-B`, `-C`)
+///
+/// Returns the flag letters before it, the value flag, and its glued value
+/// (empty when the value is the next argument): `-im5` gives `("i", 'm', "5")`.
+fn grep_value_flag(arg: &str) -> Option<(&str, char, &str)> {
+    let letters = arg.strip_prefix('-').filter(|l| !l.starts_with('-'))?;
+    let position = letters.find(['m', 'A', 'B', 'C'])?;
+    let (before, rest) = letters.split_at(position);
+    if !before.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let value = &rest[1..];
+    if !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((before, rest.chars().next()?, value))
+}
+
+/// Strip one pair of surrounding single quotes, which cmd.exe doesn't understand
+fn strip_single_quotes(arg: &str) -> &str {
+    if arg.len() >= 2 && arg.starts_with('\'') && arg.ends_with('\'') {
+        &arg[1..arg.len() - 1]
+    } else {
+        arg
+    }
+}
+
+/// Check for a bundle of short flags like `-iw`
+fn is_short_flag_bundle(arg: &str) -> bool {
+    arg.strip_prefix('-')
+        .is_some_and(|l| l.len() > 1 && l.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// Double-quote a findstr pattern that cmd.exe would otherwise split or reinterpret
+///
+/// Empty patterns, patterns with spaces and patterns containing one of cmd's
+/// metacharacters (`| & < > ^`) are quoted; anything else is left bare.
+fn quote_findstr_pattern(pattern: &str) -> String {
+    if pattern.is_empty() || pattern.contains(|c: char| c.is_whitespace() || "|&<>^".contains(c)) {
+        format!("\"{}\"", pattern)
+    } else {
+        pattern.to_string()
+    }
+}
+
+/// Split a `grep -E` alternation like `a|b` into findstr's space-separated alternatives
+///
+/// Returns `None` when the pattern has no `|`. Alternations inside groups or
+/// with spaces can't be expressed as a findstr pattern list and are kept with
+/// a warning, since findstr matches `|` literally.
+fn split_alternation(pattern: &str, result: &mut TranslationResult) -> Option<Vec<String>> {
+    if !pattern.contains('|') {
+        return None;
+    }
+    if pattern.contains(['(', ')']) || pattern.chars().any(char::is_whitespace) {
         result.warnings.push(format!(
-            "Command '{}' is already in {} format, only paths translated",
-            command_name, to_os
+            "Alternation in grep -E pattern '{}' has no findstr equivalent; findstr matches '|' literally",
+            pattern
         ));
-        return Ok(result);
+        return None;
     }
-    
-    // Command exists on both OSes - translate flags and paths
-    if is_native_command(&command_name, to_os) && is_native_command(&command_name, from_os) {
-        if let Some(mapping) = get_mapping(&command_name, from_os, to_os) {
-            let translated_args = translate_flags(&args_with_translated_paths, mapping, &mut result);
-            
-            let mut final_command = mapping.target_cmd.clone();
-            if !translated_args.is_empty() {
-                final_command.push(' ');
-                final_command.push_str(&translated_args.join(" "));
+    Some(pattern.split('|').map(str::to_string).collect())
+}
+
+/// Combine the patterns of repeated `grep -e` into findstr arguments
+///
+/// findstr treats a space-separated pattern as alternatives, so `-e a -e b`
+/// becomes `"a b"`. Patterns that are empty or contain spaces can't be joined
+/// that way and are passed as one `/c:` search string each instead.
+fn findstr_pattern_list(patterns: &[String]) -> Vec<String> {
+    if patterns.iter().any(|p| p.is_empty() || p.chars().any(char::is_whitespace)) {
+        return patterns.iter().map(|p| format!("/c:\"{}\"", p)).collect();
+    }
+    match patterns {
+        [] => Vec::new(),
+        // cmd.exe would read `|` or the `<` and `>` of word boundaries as operators
+        [single] => vec![quote_findstr_pattern(single)],
+        _ => vec![format!("\"{}\"", patterns.join(" "))],
+    }
+}
+
+/// Translate a Unix `grep` invocation to `findstr`
+///
+/// Handles the idioms that don't survive a plain flag-table lookup before
+/// translating the remaining flags through the mapping.
+fn translate_grep_to_windows(
+    args: &[String],
+    mapping: &CommandMapping,
+    result: &mut TranslationResult,
+) -> String {
+    let mut quiet = false;
+    let mut count = false;
+    let mut word = false;
+    let mut patterns = Vec::new();
+    let mut pattern_files = Vec::new();
+    let mut remaining = Vec::with_capacity(args.len());
+    let extended = args.iter().any(|a| {
+        a == "-E" || (is_short_flag_bundle(a) && !a.starts_with("-e") && !a.starts_with("-f") && a.contains('E'))
+    });
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        // Max count and context lines, possibly glued (`-m5`) or bundled (`-im5`)
+        if let Some((letters, flag, value)) = grep_value_flag(arg) {
+            if !letters.is_empty() {
+                remaining.push(format!("-{}", letters));
             }
-            
-            result.command = final_command;
-            return Ok(result);
-        } else {
-            // No flag mappings, use translated paths
-            let mut final_command = command_name.clone();
-            if !args_with_translated_paths.is_empty() {
-                final_command.push(' ');
-                final_command.push_str(&args_with_translated_paths.join(" "));
+            let value = if value.is_empty() { iter.next().map_or("", String::as_str) } else { value };
+            if value.is_empty() {
+                result.warnings.push(format!("grep -{} is missing its value and was dropped", flag));
+            } else {
+                result.warnings.push(format!("grep -{} {} has no findstr equivalent and was dropped", flag, value));
             }
-            result.command = final_command;
-            return Ok(result);
+            continue;
         }
-    }
-    
-    // Look up the command mapping
-    let mapping = match get_mapping(&command_name, from_os, to_os) {
-        Some(m) => m,
-        None => {
-            // Unix to Unix compatibility
-            if from_os.is_unix_like() && to_os.is_unix_like() {
-                let mut final_command = command_name.clone();
-                if !args_with_translated_paths.is_empty() {
-                    final_command.push(' ');
-                    final_command.push_str(&args_with_translated_paths.join(" "));
-                }
-                result.command = final_command;
-                result.warnings.push(format!(
-                    "Command '{}' passed through with path translation (Unix-like OS compatibility assumed)",
-                    command_name
-                ));
-                return Ok(result);
+
+        match arg.as_str() {
+            // findstr has no quiet mode; discard its output instead
+            "-q" | "--quiet" | "--silent" => quiet = true,
+            // findstr's /c: is a literal search string, not a count
+            "-c" | "--count" => count = true,
+            // findstr errors on unknown options and has no buffering control
+            "--line-buffered" => result.warnings.push(
+                "grep --line-buffered has no findstr equivalent and was dropped; output may be buffered".to_string(),
+            ),
+            // Patterns are collected so several `-e` can become one findstr pattern list
+            "-e" | "--regexp" => patterns.extend(iter.next().map(|p| strip_single_quotes(p).to_string())),
+            "-f" | "--file" => pattern_files.extend(iter.next().cloned()),
+            _ if arg.starts_with("--regexp=") => {
+                patterns.push(strip_single_quotes(&arg["--regexp=".len()..]).to_string());
             }
-            
-            // Check if command is already a target OS command
-            if is_target_command_for_os(&command_name, to_os) {
-                let mut final_command = command_name.clone();
-                if !args_with_translated_paths.is_empty() {
-                    final_command.push(' ');
-                    final_command.push_str(&args_with_translated_paths.join(" "));
+            _ if arg.starts_with("--file=") => pattern_files.push(arg["--file=".len()..].to_string()),
+            _ if arg.len() > 2 && arg.starts_with("-e") => {
+                patterns.push(strip_single_quotes(&arg[2..]).to_string());
+            }
+            _ if arg.len() > 2 && arg.starts_with("-f") => pattern_files.push(arg[2..].to_string()),
+            // findstr has no whole-word switch; the pattern gets word boundaries instead
+            "-w" | "--word-regexp" => word = true,
+            // The letters handled above may also be bundled (`-iw`, `-qi`, `-ic`)
+            _ if is_short_flag_bundle(arg) && arg.contains(['w', 'q', 'c']) => {
+                word |= arg.contains('w');
+                quiet |= arg.contains('q');
+                count |= arg.contains('c');
+                let letters: String = arg[1..].chars().filter(|c| !matches!(c, 'w' | 'q' | 'c')).collect();
+                if !letters.is_empty() {
+                    remaining.push(format!("-{}", letters));
                 }
-                result.command = final_command;
-                result.warnings.push(format!(
-                    "Command '{}' appears to already be a {} command, paths translated",
-                    command_name, to_os
-                ));
-                return Ok(result);
             }
-            
-            return Err(TranslationError::CommandNotFound(command_name));
+            // cmd.exe doesn't understand single quotes; patterns it would split stay quoted
+            _ if arg.len() >= 2 && arg.starts_with('\'') && arg.ends_with('\'') => {
+                remaining.push(quote_findstr_pattern(strip_single_quotes(arg)));
+            }
+            _ => remaining.push(arg.clone()),
+        }
+    }
+
+    // findstr has no `|` alternation, but matches any word of a pattern list
+    if extended && patterns.is_empty() && pattern_files.is_empty() {
+        if let Some(position) = remaining.iter().position(|a| !looks_like_flag(a)) {
+            if let Some(alternatives) = split_alternation(unquote(&remaining[position]).0, result) {
+                remaining.remove(position);
+                patterns = alternatives;
+            }
+        }
+    } else if extended {
+        let mut alternatives = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            match split_alternation(&pattern, result) {
+                Some(split) => alternatives.extend(split),
+                None => alternatives.push(pattern),
+            }
+        }
+        patterns = alternatives;
+    }
+
+    if word {
+        if patterns.is_empty() {
+            if let Some(pattern) = remaining.iter_mut().find(|a| !looks_like_flag(a)) {
+                *pattern = format!("\"\\<{}\\>\"", unquote(pattern).0);
+            }
+        } else {
+            patterns = patterns.iter().map(|p| format!("\\<{}\\>", p)).collect();
         }
+        remaining.insert(0, "-E".to_string());
+        result.warnings.push(
+            "grep -w has no findstr equivalent; emulated with a /r regex using \\< and \\> word boundaries".to_string(),
+        );
+    }
+
+    // The pattern is the first operand unless given with -e; findstr doesn't
+    // handle UTF-8 patterns reliably
+    let non_ascii = if patterns.is_empty() {
+        remaining.iter().find(|a| !looks_like_flag(a)).is_some_and(|p| !p.is_ascii())
+    } else {
+        patterns.iter().any(|p| !p.is_ascii())
+    };
+    if non_ascii {
+        result.warnings.push(
+            "Pattern contains non-ASCII characters; findstr does not support UTF-8 reliably and may miss matches".to_string(),
+        );
+    }
+
+    let mut command = mapping.target_cmd.clone();
+    let translated_args = if patterns.is_empty() && pattern_files.is_empty() {
+        translate_flags(&remaining, mapping, result)
+    } else {
+        // Explicit patterns go between the flags and the file operands
+        let (flags, operands): (Vec<String>, Vec<String>) =
+            remaining.into_iter().partition(|a| a.starts_with('-'));
+        let mut translated = translate_flags(&flags, mapping, result);
+        translated.extend(pattern_files.iter().map(|f| format!("/g:{}", f)));
+        translated.extend(findstr_pattern_list(&patterns));
+        translated.extend(operands);
+        translated
     };
-    
-    // Translate both flags and paths
-    let translated_args = translate_flags(&args_with_translated_paths, mapping, &mut result);
-    
-    // Build the final command
-    let mut final_command = mapping.target_cmd.clone();
-    
     if !translated_args.is_empty() {
-        final_command.push(' ');
-        final_command.push_str(&translated_args.join(" "));
+        command.push(' ');
+        command.push_str(&translated_args.join(" "));
     }
-    
-    result.command = final_command;
-    
-    // Add notes from mapping if any
+
+    if count {
+        command.push_str(" | find /c /v \"\"");
+        result.warnings.push(
+            "grep -c translated to a pipe into find /c; the count covers all files, not each file".to_string(),
+        );
+    }
+
+    if quiet {
+        command.push_str(" >nul");
+        result.warnings.push(
+            "grep -q translated to output redirection to nul; only the exit status is kept".to_string(),
+        );
+    }
+
     if let Some(notes) = &mapping.notes {
         result.warnings.push(notes.clone());
     }
-    
-    Ok(result)
+
+    command
 }
 
-/// Operators used in compound commands
-const COMPOUND_OPERATORS: &[&str] = &["&&", "||", ";", "|"];
+/// Translate a Unix `chmod` invocation to `attrib`
+///
+/// Windows has no permission bits; the closest match is the read-only
+/// attribute, so only the write permission carries over (`+w` clears `R`,
+/// `-w` sets it). `-R` maps to `attrib /s`.
+fn translate_chmod_to_windows(args: &[String], result: &mut TranslationResult) -> String {
+    let mut recursive = false;
+    let mut mode: Option<&str> = None;
+    let mut paths = Vec::new();
 
-/// Translate a compound command containing operators like `&&`, `||`, `;`, or `|`
+    for arg in args {
+        if mode.is_none() && is_chmod_mode(arg) {
+            mode = Some(arg);
+        } else if arg == "-R" || arg == "--recursive" {
+            recursive = true;
+        } else if arg.starts_with('-') {
+            result.warnings.push(format!("Flag '{}' was not translated", arg));
+            result.had_unmapped_flags = true;
+            result.unmapped_flags.push(arg.clone());
+        } else {
+            paths.push(arg.as_str());
+        }
+    }
+
+    let mut parts = vec!["attrib".to_string()];
+    match mode.and_then(chmod_mode_to_read_only) {
+        Some(true) => parts.push("+R".to_string()),
+        Some(false) => parts.push("-R".to_string()),
+        None => result.warnings.push(
+            "chmod mode has no attrib equivalent (only write permission maps to the read-only attribute)"
+                .to_string(),
+        ),
+    }
+    if recursive {
+        parts.push("/s".to_string());
+    }
+    parts.extend(paths.iter().map(|p| p.to_string()));
+
+    result.warnings.push(
+        "Unix permissions don't map to Windows: chmod approximated with the attrib read-only attribute"
+            .to_string(),
+    );
+
+    parts.join(" ")
+}
+
+/// Check if a chmod argument is a mode (`755`, `+x`, `u-w`, `a=rw`...)
+fn is_chmod_mode(arg: &str) -> bool {
+    if !arg.is_empty() && arg.len() <= 4 && arg.chars().all(|c| c.is_digit(8)) {
+        return true;
+    }
+    arg.split(',').all(|clause| {
+        let ops = clause.trim_start_matches(['u', 'g', 'o', 'a']);
+        let mut chars = ops.chars();
+        matches!(chars.next(), Some('+' | '-' | '='))
+            && chars.as_str().chars().all(|c| "rwxXst".contains(c))
+    })
+}
+
+/// Whether a chmod mode makes files read-only (`Some(true)`), writable
+/// (`Some(false)`), or leaves write permission untouched (`None`)
+fn chmod_mode_to_read_only(mode: &str) -> Option<bool> {
+    if mode.chars().all(|c| c.is_ascii_digit()) {
+        // Owner digit is the third from the right (e.g. 7 in 0755)
+        let owner = mode.chars().rev().nth(2).unwrap_or('0').to_digit(8)?;
+        return Some(owner & 2 == 0);
+    }
+
+    let mut read_only = None;
+    for clause in mode.split(',') {
+        let ops = clause.trim_start_matches(['u', 'g', 'o', 'a']);
+        let (op, perms) = ops.split_at(1);
+        match op {
+            "+" if perms.contains('w') => read_only = Some(false),
+            "-" if perms.contains('w') => read_only = Some(true),
+            "=" => read_only = Some(!perms.contains('w')),
+            _ => {}
+        }
+    }
+    read_only
+}
+
+/// Check whether a token is a shell variable assignment (`NAME=value`)
+fn is_variable_assignment(token: &str) -> bool {
+    match token.split_once('=') {
+        Some((name, _)) => {
+            let mut chars = name.chars();
+            matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+/// Translate leading `NAME=value` assignments of a Unix command line for cmd.exe
 ///
-/// This function splits the input by operators, translates each command individually,
-/// and then joins them back together.
+/// `FOO=bar cmd` sets `FOO` only for `cmd`; cmd.exe has no inline form, so
+/// it becomes `set "FOO=bar" & cmd & set "FOO="`. The quoted form keeps
+/// cmd from including the space before `&` in the value. A line of only
+/// assignments becomes plain `set` commands. Returns `None` when the line
+/// doesn't start with an assignment.
+fn translate_prefix_assignments(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+    translate_paths: bool,
+) -> Option<Result<TranslationResult, TranslationError>> {
+    if !from_os.is_unix_like() || to_os != Os::Windows {
+        return None;
+    }
+
+    let spans = split_argument_spans(input);
+    let count = spans
+        .iter()
+        .take_while(|&&(start, end)| is_variable_assignment(&input[start..end]))
+        .count();
+    if count == 0 {
+        return None;
+    }
+
+    let assignments: Vec<(&str, &str)> = spans[..count]
+        .iter()
+        .filter_map(|&(start, end)| input[start..end].split_once('='))
+        .map(|(name, value)| (name, unquote(value).0))
+        .collect();
+
+    let mut result = TranslationResult::new(String::new(), input.to_string(), from_os, to_os);
+
+    if count == spans.len() {
+        result.command = assignments
+            .iter()
+            .map(|(name, value)| format!("set {}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" & ");
+        result.warnings.push(
+            "Shell variable assignment translated to set; the variable is also exported to child processes".to_string(),
+        );
+        return Some(Ok(result));
+    }
+
+    let rest = &input[spans[count].0..];
+    let translated = if translate_paths {
+        translate_full_command(rest, from_os, to_os)
+    } else {
+        translate_single_command(rest, from_os, to_os)
+    };
+    let command = match translated {
+        Ok(inner) => {
+            let inner = mark_privileged(inner);
+            result.warnings.extend(inner.warnings);
+            result.requires_privilege |= inner.requires_privilege;
+            result.had_unmapped_flags |= inner.had_unmapped_flags;
+            result.unmapped_flags.extend(inner.unmapped_flags);
+            result.had_unsupported_constructs |= inner.had_unsupported_constructs;
+            result.confidence = result.confidence.max(inner.confidence);
+            inner.command
+        }
+        Err(TranslationError::CommandNotFound(_)) => {
+            result.warnings.push(format!(
+                "Command '{}' was not translated",
+                rest.split_whitespace().next().unwrap_or(rest)
+            ));
+            rest.to_string()
+        }
+        Err(e) => return Some(Err(e)),
+    };
+
+    let set: Vec<String> = assignments
+        .iter()
+        .map(|(name, value)| format!("set \"{}={}\"", name, value))
+        .collect();
+    let clear: Vec<String> = assignments
+        .iter()
+        .map(|(name, _)| format!("set \"{}=\"", name))
+        .collect();
+    result.command = format!("{} & {} & {}", set.join(" & "), command, clear.join(" & "));
+    result.warnings.push(
+        "Command-scoped variable assignment emulated with set; the variable is cleared afterwards rather than restored".to_string(),
+    );
+
+    Some(Ok(result))
+}
+
+/// Translate a `PATH` assignment (`export PATH=...` / `set PATH=...`)
+///
+/// Every entry of the list is translated in place so the original precedence
+/// order is kept. Returns `None` for assignments to other variables.
+fn translate_path_assignment(
+    args: &[String],
+    from_os: Os,
+    to_os: Os,
+    result: &mut TranslationResult,
+) -> Option<String> {
+    let assignment = args.join(" ");
+    let (name, value) = assignment.split_once('=')?;
+    if !name.eq_ignore_ascii_case("PATH") {
+        return None;
+    }
+
+    let list = translate_path_list(unquote(value).0, from_os, to_os);
+    result.warnings.extend(list.warnings);
+
+    if to_os == Os::Windows {
+        Some(format!("set {}={}", name, list.path))
+    } else {
+        Some(format!("export {}=\"{}\"", name, list.path))
+    }
+}
+
+/// Translate `set NAME=VALUE` ↔ `export NAME=VALUE`
+///
+/// `%VAR%`/`$VAR` references in the value are converted. `set /a` becomes
+/// shell arithmetic and `set /p` becomes `read -p`, both with a warning.
+/// Returns `None` when there is no assignment, e.g. a bare `set` listing.
+fn translate_variable_assignment(
+    args: &[String],
+    from_os: Os,
+    to_os: Os,
+    result: &mut TranslationResult,
+) -> Option<String> {
+    let switch = args
+        .first()
+        .filter(|a| from_os == Os::Windows && a.starts_with('/'))
+        .map(|a| a.to_lowercase());
+    let assignment = args[usize::from(switch.is_some())..].join(" ");
+    let (name, value) = unquote(&assignment).0.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    let value = translate_env_vars(unquote(value).0, from_os, to_os);
+
+    if to_os == Os::Windows {
+        return Some(format!("set {}={}", name, value));
+    }
+
+    match switch.as_deref() {
+        Some("/a") => {
+            result.warnings.push(
+                "set /a translated to shell arithmetic; cmd's 32-bit integer semantics may differ".to_string(),
+            );
+            Some(format!("export {}=$(({}))", name, value))
+        }
+        Some("/p") => {
+            result.warnings.push(
+                "set /p translated to read -p, which needs bash; the variable is not exported".to_string(),
+            );
+            Some(format!("read -r -p \"{}\" {}", value, name))
+        }
+        Some(_) => None,
+        None if value.contains(char::is_whitespace) => Some(format!("export {}=\"{}\"", name, value)),
+        None => Some(format!("export {}={}", name, value)),
+    }
+}
+
+/// Translate a command from one OS to another
+///
+/// Runs of whitespace between arguments are collapsed to a single space in
+/// the output; use [`translate_command_with_options`] to keep the original
+/// spacing of operands.
 ///
 /// # Arguments
 ///
-/// * `input` - The compound command string to translate
+/// * `input` - The command string to translate
 /// * `from_os` - The source operating system
 /// * `to_os` - The target operating system
 ///
 /// # Returns
 ///
-/// * `Ok(TranslationResult)` - The translated compound command
-/// * `Err(TranslationError)` - Error if any command translation fails
+/// * `Ok(TranslationResult)` - The translated command
+/// * `Err(TranslationError)` - Error if translation fails
 ///
 /// # Example
 ///
 /// ```
-/// use cmdx::{translate_compound_command, Os};
+/// use cmdx::{translate_command, Os};
 ///
-/// let result = translate_compound_command("dir && cls", Os::Windows, Os::Linux);
+/// let result = translate_command("dir /w", Os::Windows, Os::Linux);
 /// assert!(result.is_ok());
-/// let result = result.unwrap();
-/// assert!(result.command.contains("ls"));
-/// assert!(result.command.contains("clear"));
+/// println!("{}", result.unwrap());
 /// ```
-pub fn translate_compound_command(
+pub fn translate_command(
     input: &str,
     from_os: Os,
     to_os: Os,
 ) -> Result<TranslationResult, TranslationError> {
+    translate_redirected(input, from_os, to_os, translate_single_command)
+}
+
+/// [`translate_command`] without the privilege check
+fn translate_single_command(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+) -> Result<TranslationResult, TranslationError> {
+    // Check for empty input
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return Err(TranslationError::EmptyCommand);
     }
-
+    
     // Same OS - just return the input
     if from_os == to_os {
         return Ok(TranslationResult::new(
@@ -627,610 +1921,3848 @@ pub fn translate_compound_command(
             to_os,
         ));
     }
+    
+    // Parse the command
+    let (command_name, args) = parse_command(trimmed);
+    
+    if command_name.is_empty() {
+        return Err(TranslationError::EmptyCommand);
+    }
+
+    if let Some(result) = translate_batch_for(trimmed, from_os, to_os) {
+        return Ok(result);
+    }
+
+    if let Some(construct) = detect_unsupported_construct(trimmed, from_os, to_os) {
+        return Err(TranslationError::UnsupportedConstruct(construct));
+    }
+
+    if let Some(result) = translate_prefix_assignments(trimmed, from_os, to_os, false) {
+        return result;
+    }
+
+    // Commands that need dedicated handling beyond flag mappings
+    let mut result = TranslationResult::new(
+        String::new(),
+        trimmed.to_string(),
+        from_os,
+        to_os,
+    );
+    if let Some(command) = translate_special_command(&command_name, &args, from_os, to_os, false, &mut result) {
+        result.command = command;
+        return Ok(result);
+    }
+
+    // Check if the command is already native to the target OS
+    // If so, pass it through without transformation
+    if is_native_command(&command_name, to_os) && !is_native_command(&command_name, from_os) {
+        // Command is already in target OS format, pass through
+        let mut result = TranslationResult::new(
+            trimmed.to_string(),
+            trimmed.to_string(),
+            from_os,
+            to_os,
+        );
+        result.warnings.push(format!(
+            "Command '{}' is already in {} format, passed through unchanged",
+            command_name, to_os
+        ));
+        result.confidence = Confidence::Passthrough;
+        return Ok(result);
+    }
+    
+    // Check if the command is native to the target OS (same command on both)
+    // For example, 'ping' exists on both Windows and Linux
+    if is_native_command(&command_name, to_os) && is_native_command(&command_name, from_os) {
+        // Command exists on both OSes - check if we have flag translations
+        if let Some(mapping) = get_mapping(&command_name, from_os, to_os) {
+            // We have flag mappings, so translate the flags
+            let mut result = TranslationResult::new(
+                String::new(),
+                trimmed.to_string(),
+                from_os,
+                to_os,
+            );
+            
+            result.command = build_translated_command(&args, mapping, &mut result);
+            if let Some(notes) = &mapping.notes {
+                result.warnings.push(notes.clone());
+            }
+            return Ok(result);
+        } else {
+            // No flag mappings, pass through unchanged
+            let mut result = TranslationResult::new(
+                trimmed.to_string(),
+                trimmed.to_string(),
+                from_os,
+                to_os,
+            );
+            result.confidence = Confidence::Passthrough;
+            return Ok(result);
+        }
+    }
+    
+    // Look up the mapping
+    let mapping = match get_mapping(&command_name, from_os, to_os) {
+        Some(m) => m,
+        None => {
+            // Try to find a generic Unix-like mapping if both are Unix-like
+            if are_compatible(from_os, to_os) {
+                // Unix commands are generally compatible
+                let mut result = TranslationResult::new(
+                    trimmed.to_string(),
+                    trimmed.to_string(),
+                    from_os,
+                    to_os,
+                );
+                result.warnings.push(format!(
+                    "Command '{}' passed through (Unix-like OS compatibility assumed)",
+                    command_name
+                ));
+                result.confidence = Confidence::Passthrough;
+                return Ok(result);
+            }
+            
+            // Check if command is already a target OS command
+            if is_target_command_for_os(&command_name, to_os) {
+                let mut result = TranslationResult::new(
+                    trimmed.to_string(),
+                    trimmed.to_string(),
+                    from_os,
+                    to_os,
+                );
+                result.warnings.push(format!(
+                    "Command '{}' appears to already be a {} command, passed through unchanged",
+                    command_name, to_os
+                ));
+                result.confidence = Confidence::Passthrough;
+                return Ok(result);
+            }
+            
+            return Err(TranslationError::CommandNotFound(command_name));
+        }
+    };
+    
+    // Create result
+    let mut result = TranslationResult::new(
+        String::new(),
+        trimmed.to_string(),
+        from_os,
+        to_os,
+    );
+    
+    // Translate flags
+    result.command = build_translated_command(&args, mapping, &mut result);
+    
+    // Add notes from mapping if any
+    if let Some(notes) = &mapping.notes {
+        result.warnings.push(notes.clone());
+    }
+    
+    Ok(result)
+}
+
+/// Translate just a command name, without processing any arguments
+///
+/// Returns the base name of the target command (e.g. `md` maps to `mkdir -p`,
+/// so `mkdir` is returned). Commands already usable on the target OS are
+/// returned unchanged. Returns `None` when there is no mapping and the command
+/// isn't native-compatible with the target.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_command_name, Os};
+///
+/// assert_eq!(translate_command_name("dir", Os::Windows, Os::Linux), Some("ls".to_string()));
+/// assert_eq!(translate_command_name("nonexistent", Os::Windows, Os::Linux), None);
+/// ```
+pub fn translate_command_name(command: &str, from_os: Os, to_os: Os) -> Option<String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return None;
+    }
+    if from_os == to_os {
+        return Some(command.to_string());
+    }
+
+    if let Some(mapping) = get_mapping(command, from_os, to_os) {
+        return mapping.target_cmd.split_whitespace().next().map(str::to_string);
+    }
+
+    if is_native_command(command, to_os)
+        || are_compatible(from_os, to_os)
+        || is_target_command_for_os(command, to_os)
+    {
+        return Some(command.to_string());
+    }
+
+    None
+}
+
+/// Translate a command from one OS to another with explicit options
+///
+/// Behaves like [`translate_command`], with the differences described on
+/// [`TranslationOptions`].
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_command_with_options, Os, TranslationOptions};
+///
+/// let options = TranslationOptions { preserve_whitespace: true, ..Default::default() };
+/// let result = translate_command_with_options("echo    aligned   text", Os::Linux, Os::Windows, &options);
+/// assert_eq!(result.unwrap().command, "echo aligned   text");
+/// ```
+pub fn translate_command_with_options(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+    options: &TranslationOptions,
+) -> Result<TranslationResult, TranslationError> {
+    let powershell = options.shell == Some(Shell::PowerShell) && to_os == Os::Windows;
+
+    // The PowerShell-only forms are only worth building for a PowerShell target
+    let powershell_result = powershell.then(|| translate_powershell_command(input, from_os, to_os)).flatten();
+    let mut result = match powershell_result {
+        Some(result) => result,
+        None => translate_command(input, from_os, to_os)?,
+    };
+
+    if powershell {
+        apply_powershell_form(&mut result);
+    }
+
+    // Dropping flags re-joins the arguments, so it goes before the original
+    // spacing is restored
+    if options.minimal_coreutils && to_os.is_unix_like() {
+        drop_busybox_unsupported_flags(&mut result);
+    }
+
+    if options.preserve_whitespace {
+        result.command = restore_operand_whitespace(result.original.as_str(), &result.command);
+    }
+
+    Ok(assess_confidence(result))
+}
+
+/// Remove the GNU-only flags BusyBox would reject from a translated command
+fn drop_busybox_unsupported_flags(result: &mut TranslationResult) {
+    let command = result.command.clone();
+    let spans = split_argument_spans(&command);
+    let Some(&(start, end)) = spans.first() else { return };
+    let name = &command[start..end];
+
+    let mut kept = vec![name];
+    for &(start, end) in &spans[1..] {
+        let arg = &command[start..end];
+        let unsupported = BUSYBOX_UNSUPPORTED_FLAGS.iter().any(|&(cmd, flag)| {
+            cmd == name && (arg == flag || arg.strip_prefix(flag).is_some_and(|v| v.starts_with('=')))
+        });
+        if unsupported {
+            result.warnings.push(format!("'{}' is GNU-only and not supported by BusyBox {}; dropped", arg, name));
+        } else {
+            kept.push(arg);
+        }
+    }
+    result.command = kept.join(" ");
+}
+
+/// Translate Unix commands that only PowerShell can express faithfully
+///
+/// Returns `None` when the command has no PowerShell-specific translation,
+/// in which case the regular cmd.exe translation applies.
+fn translate_powershell_command(input: &str, from_os: Os, to_os: Os) -> Option<TranslationResult> {
+    if !from_os.is_unix_like() || to_os != Os::Windows {
+        return None;
+    }
+
+    let (command_name, args) = parse_command(input);
+    match command_name.as_str() {
+        "grep" if is_perl_regex_grep(&args) || is_recursive_grep(&args) => {
+            let mut result = TranslationResult::new(String::new(), input.trim().to_string(), from_os, to_os);
+            result.command = translate_grep_to_select_string(&args, &mut result);
+            Some(result)
+        }
+        _ => None,
+    }
+}
+
+/// Check whether a `grep` invocation uses Perl-compatible regular expressions
+fn is_perl_regex_grep(args: &[String]) -> bool {
+    args.iter().any(|a| {
+        a == "--perl-regexp"
+            || (a.starts_with('-') && !a.starts_with("--") && a[1..].chars().all(|c| c.is_ascii_alphabetic()) && a.contains('P'))
+    })
+}
+
+/// Check whether a `grep` invocation searches directories recursively
+fn is_recursive_grep(args: &[String]) -> bool {
+    args.iter().any(|a| {
+        a == "--recursive"
+            || (a.starts_with('-') && !a.starts_with("--") && a[1..].chars().all(|c| c.is_ascii_alphabetic()) && (a.contains('r') || a.contains('R')))
+    })
+}
+
+/// Translate `grep` to PowerShell's `Select-String`
+///
+/// `Select-String` is case-insensitive by default, so `-CaseSensitive` is
+/// added unless `-i` was given. Its .NET regex dialect covers most PCRE.
+/// A recursive search (`-r`) pipes `Get-ChildItem -Recurse -File` into
+/// `Select-String`, which is far closer to grep than `findstr /s`.
+fn translate_grep_to_select_string(args: &[String], result: &mut TranslationResult) -> String {
+    let mut ignore_case = false;
+    let mut invert = false;
+    let mut perl = false;
+    let mut recursive = false;
+    let mut operands = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--perl-regexp" => perl = true,
+            "--recursive" => recursive = true,
+            "--ignore-case" => ignore_case = true,
+            "--invert-match" => invert = true,
+            _ if arg.starts_with('-') && !arg.starts_with("--") && arg.len() > 1 => {
+                // Short flags may be bundled (`-Pi`)
+                for letter in arg[1..].chars() {
+                    match letter {
+                        'P' => perl = true,
+                        'r' | 'R' => recursive = true,
+                        'i' => ignore_case = true,
+                        'v' => invert = true,
+                        _ => {
+                            result.warnings.push(format!("Flag '-{}' was dropped", letter));
+                            result.had_unmapped_flags = true;
+                            result.unmapped_flags.push(format!("-{}", letter));
+                        }
+                    }
+                }
+            }
+            _ if arg.starts_with("--") => {
+                result.warnings.push(format!("Flag '{}' was dropped", arg));
+                result.had_unmapped_flags = true;
+                result.unmapped_flags.push(arg.clone());
+            }
+            _ => operands.push(arg.clone()),
+        }
+    }
+
+    let mut operands = operands.into_iter();
+    let pattern = operands.next();
+    let paths: Vec<String> = operands.collect();
+
+    let mut command = String::new();
+    if recursive {
+        // grep -r searches the current directory when no path is given
+        command.push_str("Get-ChildItem");
+        if !paths.is_empty() {
+            command.push_str(&format!(" {}", paths.join(",")));
+        }
+        command.push_str(" -Recurse -File | ");
+    }
+    command.push_str("Select-String");
+    if let Some(pattern) = pattern {
+        command.push_str(&format!(" -Pattern {}", pattern));
+    }
+    if !recursive && !paths.is_empty() {
+        command.push_str(&format!(" -Path {}", paths.join(",")));
+    }
+    if !ignore_case {
+        command.push_str(" -CaseSensitive");
+    }
+    if invert {
+        command.push_str(" -NotMatch");
+    }
+
+    if perl {
+        result.warnings.push(
+            "Perl regex translated to .NET regex; lookbehind, possessive quantifiers and \\K may behave differently".to_string(),
+        );
+    }
+    command
+}
+
+/// Swap a mapping's cmd.exe target for its PowerShell form, keeping any operands
+///
+/// The cmdlet takes none of the Unix command's flags, so those are dropped.
+fn apply_powershell_form(result: &mut TranslationResult) {
+    let command_name = match result.original.split_whitespace().next() {
+        Some(name) => name,
+        None => return,
+    };
+    let mapping = match get_mapping(command_name, result.from_os, result.to_os) {
+        Some(mapping) => mapping,
+        None => return,
+    };
+    if let Some(powershell_cmd) = &mapping.powershell_cmd {
+        if let Some(rest) = result.command.strip_prefix(mapping.target_cmd.as_str()) {
+            let (flags, operands): (Vec<&str>, Vec<&str>) = rest.split_whitespace().partition(|a| a.starts_with('-'));
+            if !flags.is_empty() {
+                result.warnings.push(format!(
+                    "Flags '{}' have no {} equivalent and were dropped",
+                    flags.join(" "),
+                    powershell_cmd
+                ));
+            }
+            result.command = std::iter::once(powershell_cmd.as_str()).chain(operands).collect::<Vec<_>>().join(" ");
+        }
+    }
+}
+
+/// Run a translation with null device redirects already rewritten
+///
+/// The redirect targets are replaced up front so `/dev/null` is never seen
+/// as a path; the result keeps the caller's input as `original`.
+fn translate_redirected(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+    translate: fn(&str, Os, Os) -> Result<TranslationResult, TranslationError>,
+) -> Result<TranslationResult, TranslationError> {
+    let redirected = translate_null_device(input, from_os, to_os);
+    let mut result = translate(&redirected, from_os, to_os)?;
+    result.original = input.trim().to_string();
+    Ok(assess_confidence(mark_privileged(result)))
+}
+
+/// Rewrite redirections to the null device (`> nul`, `2>/dev/null`)
+///
+/// Windows device names are case-insensitive, so `NUL` and `Nul` match too;
+/// the emitted device is always lowercase. Quoted text is left alone.
+fn translate_null_device(input: &str, from_os: Os, to_os: Os) -> String {
+    let (source, target) = match (from_os == Os::Windows, to_os == Os::Windows) {
+        (true, false) => ("nul", "/dev/null"),
+        (false, true) => ("/dev/null", "nul"),
+        _ => return input.to_string(),
+    };
+
+    let mut output = String::with_capacity(input.len());
+    let mut quote: Option<char> = None;
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        output.push(c);
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '>') => {
+                // `>>` appends; the target follows the last `>`
+                if rest.starts_with('>') {
+                    continue;
+                }
+                let spaced = rest.len() - rest.trim_start().len();
+                let word_len = rest[spaced..]
+                    .find(|c: char| c.is_whitespace() || "|&;<>".contains(c))
+                    .unwrap_or(rest.len() - spaced);
+                let word = &rest[spaced..spaced + word_len];
+                let matches = if from_os == Os::Windows {
+                    word.eq_ignore_ascii_case(source)
+                } else {
+                    word == source
+                };
+                if matches {
+                    output.push_str(&rest[..spaced]);
+                    output.push_str(target);
+                    rest = &rest[spaced + word_len..];
+                }
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+/// Downgrade an exact result to approximate if it carries any caveat
+///
+/// Warnings cover mapping notes as well as dropped or unknown flags, so an
+/// exact translation is one the engine had nothing to say about.
+fn assess_confidence(mut result: TranslationResult) -> TranslationResult {
+    let caveats = result.had_unmapped_flags || result.had_unsupported_constructs || !result.warnings.is_empty();
+    if result.confidence == Confidence::Exact && caveats {
+        result.confidence = Confidence::Approximate;
+    }
+    result
+}
+
+/// Set `requires_privilege` from the source and translated command names
+fn mark_privileged(mut result: TranslationResult) -> TranslationResult {
+    let source = result.original.split_whitespace().next().unwrap_or("");
+    let target = result.command.split_whitespace().next().unwrap_or("");
+    result.requires_privilege |= is_privileged(source, result.from_os) || is_privileged(target, result.to_os);
+    result
+}
+
+/// Check whether commands pass between two OSes without translation
+///
+/// This is the rule the engine uses to pass unmapped commands through.
+/// Linux, macOS and the BSDs are treated as compatible because they share
+/// the POSIX command set, but BSD and GNU tools still differ in some flags
+/// (`sed -i`, `stat -c`, `date -d`), so passthrough between them may need
+/// manual review.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{are_compatible, Os};
+///
+/// assert!(are_compatible(Os::Linux, Os::MacOS));
+/// assert!(!are_compatible(Os::Linux, Os::Windows));
+/// ```
+pub fn are_compatible(a: Os, b: Os) -> bool {
+    a == b || (a.is_unix_like() && b.is_unix_like())
+}
+
+/// Translate a command with string OS names
+pub fn translate_command_str(
+    input: &str,
+    from_os: &str,
+    to_os: &str,
+) -> Result<TranslationResult, TranslationError> {
+    let from = Os::parse(from_os)
+        .ok_or_else(|| TranslationError::InvalidOs(from_os.to_string()))?;
+    let to = Os::parse(to_os)
+        .ok_or_else(|| TranslationError::InvalidOs(to_os.to_string()))?;
+    
+    translate_command(input, from, to)
+}
+
+/// Infer the source OS of a command line from its content
+///
+/// Uses the native-command tables: a command native to only one family decides
+/// the result. Otherwise the style of any path arguments is used as a hint.
+/// Returns `None` when the input is ambiguous (e.g. `echo hello`).
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{detect_source_os, Os};
+///
+/// assert_eq!(detect_source_os("dir /w"), Some(Os::Windows));
+/// assert_eq!(detect_source_os("ls -la"), Some(Os::Linux));
+/// assert_eq!(detect_source_os("pbcopy"), Some(Os::MacOS));
+/// ```
+pub fn detect_source_os(input: &str) -> Option<Os> {
+    let (command_name, args) = parse_command(input);
+    if command_name.is_empty() {
+        return None;
+    }
+
+    let windows = is_native_command(&command_name, Os::Windows);
+    let linux = is_native_command(&command_name, Os::Linux);
+    let macos = is_native_command(&command_name, Os::MacOS);
+
+    match (windows, linux, macos) {
+        (true, false, false) => return Some(Os::Windows),
+        (false, true, _) => return Some(Os::Linux),
+        (false, false, true) => return Some(Os::MacOS),
+        _ => {}
+    }
+
+    // Fall back to the style of the arguments
+    for arg in &args {
+        let (arg, _) = unquote(arg);
+        if arg.len() > MAX_WINDOWS_FLAG_LEN && is_windows_path(arg) {
+            return Some(Os::Windows);
+        }
+        if is_unix_path(arg) {
+            return Some(Os::Linux);
+        }
+    }
+
+    None
+}
+
+/// Canonicalize a command line so equivalent forms compare equal
+///
+/// Whitespace is collapsed, flags are sorted and de-duplicated ahead of the
+/// operands, and bundled short flags are split (`-la` becomes `-a -l`). On
+/// Windows the command name and switches are lowercased, since cmd.exe
+/// ignores their case. Operands keep their order; everything after `--` is
+/// an operand. The result is meant for comparison, not for running: a flag's
+/// value may end up separated from it.
+///
+/// Useful for round-trip checks, which hold for commands whose mappings go
+/// both ways without loss: `dir`/`ls` (`/w`, `/s`, `/a`, `/b`), `copy`/`cp`,
+/// `move`/`mv`, `type`/`cat`, `cls`/`clear` and `findstr`/`grep` (`/i`, `/n`).
+/// `rm` is stable starting from Unix, but `del` gains `/f` on the way back.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{normalize_command, translate_command, Os};
+///
+/// let linux = translate_command("dir /s /w", Os::Windows, Os::Linux).unwrap().command;
+/// let back = translate_command(&linux, Os::Linux, Os::Windows).unwrap().command;
+/// assert_eq!(normalize_command(&back, Os::Windows), normalize_command("DIR /W  /S", Os::Windows));
+/// ```
+pub fn normalize_command(input: &str, os: Os) -> String {
+    let words = split_arguments(input.trim());
+    let Some((command, args)) = words.split_first() else {
+        return String::new();
+    };
+    let command = if os == Os::Windows { command.to_lowercase() } else { command.clone() };
+
+    let mut flags = Vec::new();
+    let mut operands = Vec::new();
+    let mut end_of_flags = false;
+    for arg in args {
+        if end_of_flags {
+            operands.push(arg.clone());
+        } else if arg == "--" {
+            end_of_flags = true;
+            operands.push(arg.clone());
+        } else if os == Os::Windows && arg.len() > 1 && arg.starts_with('/') {
+            flags.push(arg.to_lowercase());
+        } else if os != Os::Windows && is_short_flag_bundle(arg) {
+            flags.extend(arg[1..].chars().map(|c| format!("-{}", c)));
+        } else if os != Os::Windows && arg.len() > 1 && arg.starts_with('-') {
+            flags.push(arg.clone());
+        } else {
+            operands.push(arg.clone());
+        }
+    }
+    flags.sort();
+    flags.dedup();
+
+    std::iter::once(command).chain(flags).chain(operands).collect::<Vec<_>>().join(" ")
+}
+
+/// Batch translate multiple commands
+pub fn translate_batch(
+    commands: &[&str],
+    from_os: Os,
+    to_os: Os,
+) -> Vec<Result<TranslationResult, TranslationError>> {
+    translate_batch_iter(commands.iter().copied(), from_os, to_os).collect()
+}
+
+/// Lazily translate a sequence of commands
+///
+/// Each command is only translated when the iterator is advanced, so callers
+/// can stop early with `take_while` or `find` without translating the rest.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_batch_iter, Os};
+///
+/// let first_error = translate_batch_iter(["dir", "", "cls"], Os::Windows, Os::Linux).find(|r| r.is_err());
+/// assert!(first_error.is_some());
+/// ```
+pub fn translate_batch_iter<'a, I>(
+    commands: I,
+    from_os: Os,
+    to_os: Os,
+) -> impl Iterator<Item = Result<TranslationResult, TranslationError>> + 'a
+where
+    I: IntoIterator<Item = &'a str>,
+    I::IntoIter: 'a,
+{
+    commands
+        .into_iter()
+        .map(move |cmd| translate_command(cmd, from_os, to_os))
+}
+
+/// Translate a command with full path translation
+///
+/// This function translates both the command and any file paths in the arguments.
+/// It combines command translation with path translation for complete cross-platform conversion.
+/// Environment variable references in the arguments (`%USERPROFILE%` ↔ `$HOME`)
+/// are converted after their paths, so `%USERPROFILE%\file.txt` becomes
+/// `$HOME/file.txt`.
+///
+/// # Arguments
+///
+/// * `input` - The command string to translate (may include file paths)
+/// * `from_os` - The source operating system
+/// * `to_os` - The target operating system
+///
+/// # Returns
+///
+/// * `Ok(TranslationResult)` - The translated command with paths converted
+/// * `Err(TranslationError)` - Error if translation fails
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_full, Os};
+///
+/// // Windows command with path to Linux
+/// let result = translate_full("copy C:\\Users\\file.txt D:\\backup\\", Os::Windows, Os::Linux);
+/// assert!(result.is_ok());
+/// let r = result.unwrap();
+/// assert!(r.command.contains("cp"));
+/// assert!(r.command.contains("/mnt/c/"));
+///
+/// // Linux command with path to Windows  
+/// let result = translate_full("cp /home/user/file.txt /tmp/", Os::Linux, Os::Windows);
+/// assert!(result.is_ok());
+/// ```
+pub fn translate_full(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+) -> Result<TranslationResult, TranslationError> {
+    translate_redirected(input, from_os, to_os, translate_full_command)
+}
+
+/// [`translate_full`] without the privilege check
+fn translate_full_command(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+) -> Result<TranslationResult, TranslationError> {
+    // Check for empty input
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(TranslationError::EmptyCommand);
+    }
+    
+    // Same OS - just return the input
+    if from_os == to_os {
+        return Ok(TranslationResult::new(
+            trimmed.to_string(),
+            trimmed.to_string(),
+            from_os,
+            to_os,
+        ));
+    }
+    
+    // Parse the command
+    let (command_name, args) = parse_command(trimmed);
+    
+    if command_name.is_empty() {
+        return Err(TranslationError::EmptyCommand);
+    }
+
+    if let Some(result) = translate_batch_for(trimmed, from_os, to_os) {
+        return Ok(result);
+    }
+
+    if let Some(construct) = detect_unsupported_construct(trimmed, from_os, to_os) {
+        return Err(TranslationError::UnsupportedConstruct(construct));
+    }
+
+    if let Some(result) = translate_prefix_assignments(trimmed, from_os, to_os, true) {
+        return result;
+    }
+    
+    let mut result = TranslationResult::new(
+        String::new(),
+        trimmed.to_string(),
+        from_os,
+        to_os,
+    );
+
+    // Commands that need dedicated handling beyond flag mappings
+    if let Some(command) = translate_special_command(&command_name, &args, from_os, to_os, true, &mut result) {
+        result.command = command;
+        return Ok(result);
+    }
+
+    // First translate the paths in arguments
+    let args_with_translated_paths = translate_path_args(&args, from_os, to_os, &mut result);
+
+    // Check if the command is already native to the target OS
+    if is_native_command(&command_name, to_os) && !is_native_command(&command_name, from_os) {
+        // Command is already in target OS format, just use translated paths
+        let mut final_command = command_name.clone();
+        if !args_with_translated_paths.is_empty() {
+            final_command.push(' ');
+            final_command.push_str(&args_with_translated_paths.join(" "));
+        }
+        result.command = final_command;
+        result.warnings.push(format!(
+            "Command '{}' is already in {} format, only paths translated",
+            command_name, to_os
+        ));
+        result.confidence = Confidence::Passthrough;
+        return Ok(result);
+    }
+    
+    // Command exists on both OSes - translate flags and paths
+    if is_native_command(&command_name, to_os) && is_native_command(&command_name, from_os) {
+        if let Some(mapping) = get_mapping(&command_name, from_os, to_os) {
+            result.command = build_translated_command(&args_with_translated_paths, mapping, &mut result);
+            if let Some(notes) = &mapping.notes {
+                result.warnings.push(notes.clone());
+            }
+            return Ok(result);
+        } else {
+            // No flag mappings, use translated paths
+            let mut final_command = command_name.clone();
+            if !args_with_translated_paths.is_empty() {
+                final_command.push(' ');
+                final_command.push_str(&args_with_translated_paths.join(" "));
+            }
+            result.command = final_command;
+            result.confidence = Confidence::Passthrough;
+            return Ok(result);
+        }
+    }
+    
+    // Look up the command mapping
+    let mapping = match get_mapping(&command_name, from_os, to_os) {
+        Some(m) => m,
+        None => {
+            // Unix to Unix compatibility
+            if are_compatible(from_os, to_os) {
+                let mut final_command = command_name.clone();
+                if !args_with_translated_paths.is_empty() {
+                    final_command.push(' ');
+                    final_command.push_str(&args_with_translated_paths.join(" "));
+                }
+                result.command = final_command;
+                result.warnings.push(format!(
+                    "Command '{}' passed through with path translation (Unix-like OS compatibility assumed)",
+                    command_name
+                ));
+                result.confidence = Confidence::Passthrough;
+                return Ok(result);
+            }
+            
+            // Check if command is already a target OS command
+            if is_target_command_for_os(&command_name, to_os) {
+                let mut final_command = command_name.clone();
+                if !args_with_translated_paths.is_empty() {
+                    final_command.push(' ');
+                    final_command.push_str(&args_with_translated_paths.join(" "));
+                }
+                result.command = final_command;
+                result.warnings.push(format!(
+                    "Command '{}' appears to already be a {} command, paths translated",
+                    command_name, to_os
+                ));
+                result.confidence = Confidence::Passthrough;
+                return Ok(result);
+            }
+            
+            return Err(TranslationError::CommandNotFound(command_name));
+        }
+    };
+    
+    // Translate both flags and paths
+    result.command = build_translated_command(&args_with_translated_paths, mapping, &mut result);
+    
+    // Add notes from mapping if any
+    if let Some(notes) = &mapping.notes {
+        result.warnings.push(notes.clone());
+    }
+    
+    Ok(result)
+}
+
+/// Operators recognized when splitting compound commands
+pub const COMPOUND_OPERATORS: &[&str] = &["&&", "||", ";", "|", "&"];
+
+/// Get the operators recognized when splitting compound commands
+///
+/// Useful for tools that pre-split input and want to match cmdx exactly.
+pub fn compound_operators() -> &'static [&'static str] {
+    COMPOUND_OPERATORS
+}
+
+/// Translate a compound command containing operators like `&&`, `||`, `;`, or `|`
+///
+/// This function splits the input by operators, translates each command individually,
+/// and then joins them back together. The `;` separator becomes `&` for cmd.exe,
+/// and cmd's `&` becomes `;` for Unix shells.
+///
+/// # Arguments
+///
+/// * `input` - The compound command string to translate
+/// * `from_os` - The source operating system
+/// * `to_os` - The target operating system
+///
+/// # Returns
+///
+/// * `Ok(TranslationResult)` - The translated compound command
+/// * `Err(TranslationError)` - Error if any command translation fails
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_compound_command, Os};
+///
+/// let result = translate_compound_command("dir && cls", Os::Windows, Os::Linux);
+/// assert!(result.is_ok());
+/// let result = result.unwrap();
+/// assert!(result.command.contains("ls"));
+/// assert!(result.command.contains("clear"));
+/// ```
+pub fn translate_compound_command(
+    input: &str,
+    from_os: Os,
+    to_os: Os,
+) -> Result<TranslationResult, TranslationError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(TranslationError::EmptyCommand);
+    }
+
+    // Same OS - just return the input
+    if from_os == to_os {
+        return Ok(TranslationResult::new(
+            trimmed.to_string(),
+            trimmed.to_string(),
+            from_os,
+            to_os,
+        ));
+    }
+
+    // A batch `for` loop's body is translated as a unit with the loop
+    if let Some(result) = translate_batch_for(trimmed, from_os, to_os) {
+        return Ok(assess_confidence(result));
+    }
+
+    // Split the command by operators while preserving the operators
+    let (parts, cleaned) = drop_empty_segments(split_compound_command_for(trimmed, from_os));
+    let cleanup_warning = "Empty command segments and repeated operators were removed";
+
+    // If there's only one part, use regular translation
+    if parts.len() <= 1 && parts.first().is_none_or(|p| split_redirections(p).1.is_empty()) {
+        let segment = parts.first().ok_or(TranslationError::EmptyCommand)?;
+        let mut result = translate_command(segment, from_os, to_os)?;
+        if cleaned {
+            result.original = trimmed.to_string();
+            result.warnings.push(cleanup_warning.to_string());
+        }
+        return Ok(assess_confidence(result));
+    }
+
+    let mut result = TranslationResult::new(
+        String::new(),
+        trimmed.to_string(),
+        from_os,
+        to_os,
+    );
+    if cleaned {
+        result.warnings.push(cleanup_warning.to_string());
+    }
+
+    // Leave commands that were already translated (or written for the target)
+    // alone rather than reverse-mapping them; only the operators are translated
+    if is_already_translated(&parts, from_os, to_os) {
+        result.command = parts
+            .iter()
+            .map(|p| p.trim())
+            .map(|p| if COMPOUND_OPERATORS.contains(&p) { translate_operator(p, from_os, to_os) } else { p })
+            .collect::<Vec<_>>()
+            .join(" ");
+        result.warnings.push(format!(
+            "Line is already in {} format (already translated), passed through unchanged",
+            to_os
+        ));
+        result.requires_privilege = parts
+            .iter()
+            .filter_map(|p| p.split_whitespace().next())
+            .any(|cmd| is_privileged(cmd, to_os));
+        result.confidence = Confidence::Passthrough;
+        return Ok(result);
+    }
+
+    let mut translated_parts: Vec<String> = Vec::new();
+    let mut loop_end = 0;
+    
+    for (i, part) in parts.iter().enumerate() {
+        let trimmed_part = part.trim();
+
+        // Segments of a one-line loop were already emitted as a unit
+        if i < loop_end {
+            continue;
+        }
+        if let Some((text, end)) = translate_shell_loop(&parts[i..], from_os, to_os, &mut result) {
+            translated_parts.push(text);
+            loop_end = i + end;
+            continue;
+        }
+        
+        // A bash `&` runs the preceding command in the background; cmd.exe's
+        // closest form is `start /b`. A trailing `&` only backgrounds, while
+        // mid-line it also separates the next command (cmd's `&`).
+        if trimmed_part == "&" && from_os.is_unix_like() && to_os == Os::Windows {
+            if let Some(previous) = translated_parts.last_mut().filter(|p| !COMPOUND_OPERATORS.contains(&p.as_str())) {
+                *previous = format!("start /b {}", previous);
+                result.warnings.push(
+                    "Background job emulated with start /b; job control (jobs, fg, wait) is not available".to_string(),
+                );
+            }
+            if parts[i + 1..].iter().any(|p| !p.trim().is_empty()) {
+                translated_parts.push("&".to_string());
+            }
+            continue;
+        }
+
+        // cmd's `&` only separates commands, so a trailing one is meaningless
+        if trimmed_part == "&" && from_os == Os::Windows && parts[i + 1..].iter().all(|p| p.trim().is_empty()) {
+            continue;
+        }
+
+        // Check if this part is an operator
+        if COMPOUND_OPERATORS.contains(&trimmed_part) {
+            translated_parts.push(translate_operator(trimmed_part, from_os, to_os).to_string());
+        } else if is_segment_in_target_form(trimmed_part, from_os, to_os) {
+            translated_parts.push(trimmed_part.to_string());
+            result.warnings.push(format!(
+                "Command '{}' is already in {} format, passed through unchanged",
+                trimmed_part.split_whitespace().next().unwrap_or(trimmed_part),
+                to_os
+            ));
+            result.confidence = result.confidence.max(Confidence::Passthrough);
+        } else if !trimmed_part.is_empty() {
+            // Redirections are split off so their targets aren't read as
+            // arguments, and translated separately as paths
+            let redirected = translate_null_device(trimmed_part, from_os, to_os);
+            let (command, redirections) = split_redirections(&redirected);
+            let redirections = translate_redirections(&redirections, from_os, to_os, &mut result);
+            let command = if command.is_empty() { trimmed_part } else { command.as_str() };
+
+            // Translate the command
+            match translate_command(command, from_os, to_os) {
+                Ok(cmd_result) => {
+                    translated_parts.push(format!("{}{}", cmd_result.command, redirections));
+                    // Collect warnings
+                    result.warnings.extend(cmd_result.warnings);
+                    result.had_unmapped_flags |= cmd_result.had_unmapped_flags;
+                    result.unmapped_flags.extend(cmd_result.unmapped_flags);
+                    result.requires_privilege |= cmd_result.requires_privilege;
+                    result.confidence = result.confidence.max(cmd_result.confidence);
+                }
+                Err(TranslationError::CommandNotFound(_)) => {
+                    // Keep original command if not found (might be a custom/unknown command)
+                    translated_parts.push(trimmed_part.to_string());
+                    result.warnings.push(format!("Command '{}' was not translated", trimmed_part.split_whitespace().next().unwrap_or(trimmed_part)));
+                    result.confidence = result.confidence.max(Confidence::Passthrough);
+                }
+                Err(TranslationError::UnsupportedConstruct(construct)) => {
+                    // Not fatal within a compound line: keep the segment as written
+                    translated_parts.push(trimmed_part.to_string());
+                    result.warnings.push(format!("Unsupported shell construct ({}) passed through unchanged", construct));
+                    result.had_unsupported_constructs = true;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    result.command = translated_parts.join(" ");
+    Ok(assess_confidence(result))
+}
+
+/// Keep a one-line bash `for`/`while`/`until` loop together
+///
+/// `parts` starts at the segment that may open a loop. The loop keywords and
+/// its separators are kept as written and only the commands in the body are
+/// translated, best-effort. Returns the loop text and the number of parts it
+/// spans, or `None` if `parts` doesn't start a complete loop.
+fn translate_shell_loop(
+    parts: &[String],
+    from_os: Os,
+    to_os: Os,
+    result: &mut TranslationResult,
+) -> Option<(String, usize)> {
+    const LOOP_KEYWORDS: &[&str] = &["for", "while", "until"];
+
+    let keyword = parts.first()?.split_whitespace().next()?;
+    if !from_os.is_unix_like() || !LOOP_KEYWORDS.contains(&keyword) {
+        return None;
+    }
+
+    // Find the `done` that closes this loop, counting nested loops
+    let mut depth = 0;
+    let end = parts.iter().position(|part| {
+        let words: Vec<&str> = part.split_whitespace().take(2).collect();
+        let opener = match words[..] {
+            ["do", word, ..] | [word, ..] => word,
+            [] => return false,
+        };
+        if LOOP_KEYWORDS.contains(&opener) {
+            depth += 1;
+        } else if words[0] == "done" {
+            depth -= 1;
+        }
+        depth == 0
+    })?;
+
+    let mut text = String::new();
+    for (i, part) in parts[..=end].iter().enumerate() {
+        let part = part.trim();
+        let (prefix, body) = match part.strip_prefix("do ") {
+            Some(body) => ("do ", body.trim_start()),
+            None => ("", part),
+        };
+        let first = body.split_whitespace().next().unwrap_or("");
+        let is_keyword = i == 0
+            || COMPOUND_OPERATORS.contains(&body)
+            || matches!(first, "for" | "while" | "until" | "do" | "done" | "if" | "then" | "else" | "fi");
+        let body = if is_keyword {
+            body.to_string()
+        } else {
+            match translate_command(body, from_os, to_os) {
+                Ok(translated) => {
+                    result.warnings.extend(translated.warnings);
+                    result.had_unmapped_flags |= translated.had_unmapped_flags;
+                    result.unmapped_flags.extend(translated.unmapped_flags);
+                    result.requires_privilege |= translated.requires_privilege;
+                    result.confidence = result.confidence.max(translated.confidence);
+                    translated.command
+                }
+                Err(_) => body.to_string(),
+            }
+        };
+        if body == ";" {
+            text.push(';');
+        } else {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(prefix);
+            text.push_str(&body);
+        }
+    }
+
+    if !to_os.is_unix_like() {
+        result.warnings.push(format!(
+            "Shell loop ('{}') passed through as one unit; only the commands in its body were translated",
+            keyword
+        ));
+        result.had_unsupported_constructs = true;
+    }
+    Some((text, end + 1))
+}
+
+/// Translate a batch `for` loop into a bash loop
+///
+/// Handles the plain form (`for %%i in (*.txt) do ...` becomes
+/// `for i in *.txt; do ...; done`), `for /l` counting loops via `seq`, and the
+/// `for /f` line-reading idiom, which becomes a `while read` loop fed from
+/// the file, string or command. Only the common token and delimiter specs
+/// are understood; others are reported in a warning. Returns `None` for
+/// forms that can't be translated, such as `for /d`, `for /r` and loops
+/// whose block continues on the next line.
+fn translate_batch_for(input: &str, from_os: Os, to_os: Os) -> Option<TranslationResult> {
+    if from_os != Os::Windows || !to_os.is_unix_like() {
+        return None;
+    }
+    let trimmed = input.trim();
+    let rest = strip_keyword(trimmed, "for ")?;
+
+    let mut result = TranslationResult::new(String::new(), trimmed.to_string(), from_os, to_os);
+
+    // Switch and, for /f, its quoted options
+    let (switch, rest) = match rest.strip_prefix('/') {
+        Some(after) => {
+            let end = after.find(char::is_whitespace)?;
+            (after[..end].to_lowercase(), after[end..].trim_start())
+        }
+        None => (String::new(), rest),
+    };
+    let (options, rest) = match rest.strip_prefix('"') {
+        Some(after) if switch == "f" => {
+            let end = after.find('"')?;
+            (&after[..end], after[end + 1..].trim_start())
+        }
+        _ => ("", rest),
+    };
+
+    // Loop variable: `%%i` in scripts, `%i` on the command line
+    let (variable, rest) = rest.split_once(char::is_whitespace)?;
+    let name = variable.trim_start_matches('%');
+    if !variable.starts_with('%') || name.len() != 1 || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let rest = strip_keyword(rest.trim_start(), "in ")?;
+
+    // The parenthesized set, whose parentheses may appear inside quotes
+    let inner = rest.strip_prefix('(')?;
+    let mut quote = None;
+    let (close, _) = inner.char_indices().find(|&(_, c)| {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if matches!(c, '"' | '\'' | '`') => quote = Some(c),
+            None => return c == ')',
+        }
+        false
+    })?;
+    let set = inner[..close].trim();
+    let body = strip_keyword(inner[close + 1..].trim_start(), "do ")?.trim_end();
+
+    // A `(` block may continue on the following lines
+    let body = match body.strip_prefix('(') {
+        Some(block) => block.strip_suffix(')')?.trim(),
+        None => body,
+    };
+    if body.is_empty() {
+        return None;
+    }
+
+    // References to the loop variable, including `%%~i`, become `$i`
+    let reference = format!("${}", name);
+    let percent = &variable[..variable.len() - 1];
+    let mut body = body.to_string();
+    for form in [format!("{}~{}", percent, name), variable.to_string()] {
+        body = body.replace(&form, &reference);
+    }
+    let body = match translate_compound_command(&body, from_os, to_os) {
+        Ok(translated) => {
+            result.warnings.extend(translated.warnings);
+            result.had_unmapped_flags |= translated.had_unmapped_flags;
+            result.unmapped_flags.extend(translated.unmapped_flags);
+            result.had_unsupported_constructs |= translated.had_unsupported_constructs;
+            result.requires_privilege |= translated.requires_privilege;
+            translated.command
+        }
+        Err(_) => body,
+    };
+
+    let translate_item = |item: &str| match translate_path(item, from_os, to_os) {
+        Ok(path) if is_windows_path(item) => path.path,
+        _ => item.to_string(),
+    };
+
+    result.command = match switch.as_str() {
+        "" => {
+            let items: Vec<String> = set
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                .filter(|item| !item.is_empty())
+                .map(translate_item)
+                .collect();
+            format!("for {} in {}; do {}; done", name, items.join(" "), body)
+        }
+        "l" => {
+            let bounds: Vec<&str> = set.split(',').map(str::trim).collect();
+            if bounds.len() != 3 {
+                return None;
+            }
+            format!("for {} in $(seq {}); do {}; done", name, bounds.join(" "), body)
+        }
+        "f" => {
+            let (mut tokens, mut delims, mut usebackq) = (None, None, false);
+            for option in options.split_whitespace() {
+                match option.split_once('=') {
+                    Some(("tokens", value)) => tokens = Some(value),
+                    Some(("delims", value)) => delims = Some(value),
+                    None if option == "usebackq" => usebackq = true,
+                    _ => result.warnings.push(format!("'for /f' option '{}' is not supported and was ignored", option)),
+                }
+            }
+            // `delims=` must be last, and a space there is a delimiter itself
+            if options.trim_end().ends_with("delims=") && options.ends_with(' ') {
+                delims = Some(" ");
+            }
+            if tokens.is_some_and(|t| t != "*" && t != "1") {
+                result.warnings.push(format!(
+                    "'for /f' token spec 'tokens={}' is not supported; ${} holds the whole line",
+                    tokens.unwrap_or_default(),
+                    name
+                ));
+            }
+
+            let read = match (tokens, delims) {
+                (Some("1") | None, Some(d)) if !d.is_empty() => format!("IFS='{}' read -r {} _", d, name),
+                (Some("1") | None, None) => format!("read -r {} _", name),
+                _ => format!("IFS= read -r {}", name),
+            };
+            let (command_quote, string_quote) = if usebackq { ('`', '\'') } else { ('\'', '"') };
+            let source = set
+                .chars()
+                .next()
+                .filter(|&q| set.len() >= 2 && set.ends_with(q) && (q == command_quote || q == string_quote || q == '"'));
+            let (feed, redirect) = match source {
+                Some(q) if q == command_quote => {
+                    let command = &set[1..set.len() - 1];
+                    let command = translate_compound_command(command, from_os, to_os)
+                        .map(|translated| translated.command)
+                        .unwrap_or_else(|_| command.to_string());
+                    (format!("{} | ", command), String::new())
+                }
+                Some(q) if q == string_quote => (format!("echo \"{}\" | ", &set[1..set.len() - 1]), String::new()),
+                // A double-quoted file name with usebackq
+                Some(_) => (String::new(), format!(" < \"{}\"", translate_item(&set[1..set.len() - 1]))),
+                None => (String::new(), format!(" < {}", translate_item(set))),
+            };
+            result.warnings.push(
+                "'for /f' translated approximately to a while-read loop; check the token and delimiter handling".to_string(),
+            );
+            format!("{}while {}; do {}; done{}", feed, read, body, redirect)
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Strip a leading keyword (including its trailing space), ignoring case
+fn strip_keyword<'a>(input: &'a str, keyword: &str) -> Option<&'a str> {
+    let head = input.get(..keyword.len())?;
+    head.eq_ignore_ascii_case(keyword).then(|| input[keyword.len()..].trim_start())
+}
+
+/// Remove empty segments and the stray operators around them
+///
+/// Messy input like `; dir ;` or `dir ;; cls` splits into empty segments
+/// and back-to-back operators. Leading operators and repeats are dropped, as
+/// are trailing ones except `&`, which backgrounds the last command in bash.
+/// Returns the cleaned parts and whether anything was removed.
+fn drop_empty_segments(parts: Vec<String>) -> (Vec<String>, bool) {
+    let is_operator = |part: &str| COMPOUND_OPERATORS.contains(&part.trim());
+    let mut cleaned = false;
+    let mut kept: Vec<String> = Vec::with_capacity(parts.len());
+
+    for part in parts {
+        let stray = is_operator(&part) && kept.last().is_none_or(|last| is_operator(last));
+        if part.trim().is_empty() || stray {
+            cleaned = true;
+        } else {
+            kept.push(part);
+        }
+    }
+
+    if kept.last().is_some_and(|last| is_operator(last) && last.trim() != "&") {
+        kept.pop();
+        cleaned = true;
+    }
+
+    (kept, cleaned)
+}
+
+/// Check whether a single segment of a compound line is written for the target OS
+///
+/// Each segment is analyzed on its own, so mixed lines like
+/// `find . -name x | findstr y` work. Commands native to both OSes (`find`,
+/// `sort`) are judged by their flag style; other commands are handled by the
+/// native-command checks in `translate_command`.
+/// Flags the source mapping knows always mark the segment as source form.
+fn is_segment_in_target_form(segment: &str, from_os: Os, to_os: Os) -> bool {
+    if from_os.is_unix_like() == to_os.is_unix_like() {
+        return false;
+    }
+
+    let (command_name, args) = parse_command(segment);
+    if !is_native_command(&command_name, from_os) || !is_native_command(&command_name, to_os) {
+        return false;
+    }
+
+    // Flags the source mapping knows (e.g. ping's `-n` on Windows) mark source form
+    if let Some(mapping) = get_mapping(&command_name, from_os, to_os) {
+        if args.iter().any(|a| mapping.flag_mappings.iter().any(|f| f.source.eq_ignore_ascii_case(a))) {
+            return false;
+        }
+    }
+
+    let dash_flags = args.iter().any(|a| a.len() > 1 && a.starts_with('-'));
+    let slash_flags = args
+        .iter()
+        .any(|a| a.len() > 1 && a.starts_with('/') && a.len() <= MAX_WINDOWS_FLAG_LEN);
+
+    if to_os.is_unix_like() {
+        dash_flags && !slash_flags
+    } else {
+        slash_flags && !dash_flags
+    }
+}
+
+/// Check whether every command in a split compound line is already in target form
+///
+/// Requires at least one command that only exists on the target OS; commands
+/// shared by both OSes count only if they have no flag mapping to apply.
+fn is_already_translated(parts: &[String], from_os: Os, to_os: Os) -> bool {
+    let mut has_target_only = false;
+
+    for part in parts.iter().map(|p| p.trim()) {
+        if part.is_empty() || COMPOUND_OPERATORS.contains(&part) {
+            continue;
+        }
+        let command = match part.split_whitespace().next() {
+            Some(c) => c.to_lowercase(),
+            None => continue,
+        };
+
+        if !is_native_command(&command, to_os) && !is_target_command_for_os(&command, to_os) {
+            return false;
+        }
+        if is_native_command(&command, from_os) {
+            if get_mapping(&command, from_os, to_os).is_some() {
+                return false;
+            }
+        } else {
+            has_target_only = true;
+        }
+    }
+
+    has_target_only
+}
+
+/// Split a compound command by operators while preserving the operators
+///
+/// Operators inside quotes are not split on. A single `&` is the cmd.exe
+/// sequential separator; `&` that is part of a redirection (`2>&1`, `&>`)
+/// is left in place.
+pub(crate) fn split_compound_command(input: &str) -> Vec<String> {
+    split_operators(input, true)
+}
+
+/// [`split_compound_command`] for a command line written for `os`
+///
+/// cmd.exe has no `;` separator and uses `;` inside values such as
+/// `PATH`, so it only separates commands from a Unix shell.
+pub(crate) fn split_compound_command_for(input: &str, os: Os) -> Vec<String> {
+    split_operators(input, os.is_unix_like())
+}
+
+fn split_operators(input: &str, split_semicolons: bool) -> Vec<String> {
+    // Operators and quotes are ASCII, and UTF-8 continuation bytes never are,
+    // so scanning bytes only ever splits on character boundaries.
+    let bytes = input.as_bytes();
+    let mut parts = Vec::new();
+    let mut quote: Option<u8> = None;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if let Some(q) = quote {
+            if b == q {
+                quote = None;
+            }
+            i += 1;
+            continue;
+        }
+        if b == b'"' || b == b'\'' {
+            quote = Some(b);
+            i += 1;
+            continue;
+        }
+
+        // Check for two-character operators first, then single-character ones
+        let is_redirect_amp = b == b'&'
+            && (i > 0 && matches!(bytes[i - 1], b'>' | b'<') || bytes.get(i + 1) == Some(&b'>'));
+        let len = match (b, bytes.get(i + 1)) {
+            (b'&', Some(b'&')) | (b'|', Some(b'|')) => 2,
+            (b'|', _) => 1,
+            (b';', _) if split_semicolons => 1,
+            (b'&', _) if !is_redirect_amp => 1,
+            _ => 0,
+        };
+        if len == 0 {
+            i += 1;
+            continue;
+        }
+
+        if start < i {
+            parts.push(input[start..i].to_string());
+        }
+        parts.push(input[i..i + len].to_string());
+        i += len;
+        start = i;
+    }
+
+    if start < bytes.len() {
+        parts.push(input[start..].to_string());
+    }
+
+    parts
+}
+
+/// A redirection split off a command segment, e.g. `2>` and `err.txt`
+struct Redirection {
+    operator: String,
+    target: String,
+    /// Whether the operator and target were separated by whitespace
+    spaced: bool,
+}
+
+/// Split the redirections (`>`, `>>`, `2>`, `<`, `2>&1`) off a command segment
+///
+/// Returns the command and its redirections in their original order.
+/// Quoted text is left alone.
+fn split_redirections(segment: &str) -> (String, Vec<Redirection>) {
+    let bytes = segment.as_bytes();
+    let mut command = String::with_capacity(segment.len());
+    let mut redirections = Vec::new();
+    let mut quote: Option<u8> = None;
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if let Some(q) = quote {
+            if b == q {
+                quote = None;
+            }
+            i += 1;
+            continue;
+        }
+        if b == b'"' || b == b'\'' {
+            quote = Some(b);
+            i += 1;
+            continue;
+        }
+        if b != b'>' && b != b'<' {
+            i += 1;
+            continue;
+        }
+        // Heredocs and process substitution aren't plain redirections; the
+        // segment is kept whole so they are reported as unsupported
+        if (b == b'<' && bytes.get(i + 1) == Some(&b'<')) || bytes.get(i + 1) == Some(&b'(') {
+            return (segment.trim().to_string(), Vec::new());
+        }
+
+        // A file descriptor or `&` directly before the operator belongs to it
+        let at_word_start = |j: usize| j == 0 || bytes[j - 1].is_ascii_whitespace();
+        let start = if i > 0 && (bytes[i - 1].is_ascii_digit() || bytes[i - 1] == b'&') && at_word_start(i - 1) {
+            i - 1
+        } else {
+            i
+        };
+        let mut end = i + 1;
+        if b == b'>' && bytes.get(end) == Some(&b'>') {
+            end += 1;
+        }
+        if bytes.get(end) == Some(&b'&') {
+            end += 1;
+        }
+
+        let target_start = end + segment[end..].len() - segment[end..].trim_start().len();
+        let target_end = segment[target_start..]
+            .find(char::is_whitespace)
+            .map_or(segment.len(), |n| target_start + n);
+
+        command.push_str(&segment[copied..start]);
+        redirections.push(Redirection {
+            operator: segment[start..end].to_string(),
+            target: segment[target_start..target_end].to_string(),
+            spaced: target_start > end,
+        });
+        copied = target_end;
+        i = target_end;
+    }
+
+    command.push_str(&segment[copied..]);
+    (command.trim().to_string(), redirections)
+}
+
+/// Re-emit split-off redirections, translating targets that are paths
+fn translate_redirections(
+    redirections: &[Redirection],
+    from_os: Os,
+    to_os: Os,
+    result: &mut TranslationResult,
+) -> String {
+    redirections
+        .iter()
+        .map(|r| {
+            let target = if r.operator.ends_with('&') {
+                r.target.clone()
+            } else {
+                translate_path_args(std::slice::from_ref(&r.target), from_os, to_os, result).remove(0)
+            };
+            let space = if r.spaced { " " } else { "" };
+            format!(" {}{}{}", r.operator, space, target)
+        })
+        .collect()
+}
+
+/// Translate a statement separator between shell families
+///
+/// cmd.exe has no `;` separator; its sequential operator is a single `&`,
+/// which bash would instead read as "run in background".
+pub(crate) fn translate_operator(op: &str, from_os: Os, to_os: Os) -> &str {
+    match op {
+        ";" if from_os.is_unix_like() && !to_os.is_unix_like() => "&",
+        "&" if !from_os.is_unix_like() && to_os.is_unix_like() => ";",
+        _ => op,
+    }
+}
+
+/// A script file name renamed for another OS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptRename {
+    /// The file name with its extension swapped
+    pub name: String,
+    /// Set when the extension has no equivalent and the name was kept
+    pub warning: Option<String>,
+}
+
+/// Translate a script file extension between operating systems
+///
+/// # Arguments
+///
+/// * `filename` - The filename with extension to translate
+/// * `from_os` - The source operating system
+/// * `to_os` - The target operating system
+///
+/// # Returns
+///
+/// The filename with translated extension
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_script_extension, Os};
+///
+/// let result = translate_script_extension("script.bat", Os::Windows, Os::Linux);
+/// assert_eq!(result, "script.sh");
+///
+/// let result = translate_script_extension("script.sh", Os::Linux, Os::Windows);
+/// assert_eq!(result, "script.bat");
+/// ```
+pub fn translate_script_extension(filename: &str, from_os: Os, to_os: Os) -> String {
+    translate_script_filename(filename, from_os, to_os).name
+}
+
+/// Translate a script file name, warning when its extension is unknown
+///
+/// `.bat`, `.cmd` and `.ps1` become `.sh` and `.exe` is dropped going to
+/// Unix; `.sh` becomes `.bat` and extensionless files get `.exe` going to
+/// Windows. The stem and any directory components are preserved. Other
+/// extensions are returned unchanged with a warning.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_script_filename, Os};
+///
+/// let result = translate_script_filename("scripts\\install.bat", Os::Windows, Os::Linux);
+/// assert_eq!(result.name, "scripts\\install.sh");
+///
+/// let result = translate_script_filename("notes.txt", Os::Windows, Os::Linux);
+/// assert_eq!(result.name, "notes.txt");
+/// assert!(result.warning.is_some());
+/// ```
+pub fn translate_script_filename(filename: &str, from_os: Os, to_os: Os) -> ScriptRename {
+    let renamed = |name: String| ScriptRename { name, warning: None };
+    if from_os == to_os {
+        return renamed(filename.to_string());
+    }
+    
+    let filename = filename.trim();
+    
+    // Windows to Unix
+    if from_os == Os::Windows && to_os.is_unix_like() {
+        let filename_lower = filename.to_lowercase();
+        if let Some(base) = filename_lower.strip_suffix(".bat") {
+            return renamed(format!("{}.sh", &filename[..base.len()]));
+        }
+        if let Some(base) = filename_lower.strip_suffix(".cmd") {
+            return renamed(format!("{}.sh", &filename[..base.len()]));
+        }
+        if let Some(base) = filename_lower.strip_suffix(".ps1") {
+            return renamed(format!("{}.sh", &filename[..base.len()]));
+        }
+        if let Some(base) = filename_lower.strip_suffix(".exe") {
+            return renamed(filename[..base.len()].to_string());
+        }
+    }
+    
+    // Unix to Windows
+    if from_os.is_unix_like() && to_os == Os::Windows {
+        if let Some(base) = filename.strip_suffix(".sh") {
+            return renamed(format!("{}.bat", base));
+        }
+        // Files without extension might be executables - check using Path for robustness
+        if let Some(file_name) = std::path::Path::new(filename).file_name() {
+            let name = file_name.to_string_lossy();
+            if !name.contains('.') {
+                return renamed(format!("{}.exe", filename));
+            }
+        }
+    }
+
+    // Only the last path component's extension counts, in either separator style
+    let base_name = filename.rsplit(['/', '\\']).next().unwrap_or(filename);
+    let warning = match base_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && from_os.is_unix_like() != to_os.is_unix_like() => Some(format!(
+            "Extension '.{}' has no {} equivalent; file name kept as '{}'",
+            extension, to_os, filename
+        )),
+        _ => None,
+    };
+    ScriptRename { name: filename.to_string(), warning }
+}
+
+/// Write a translated script with the line endings and permissions of its target
+///
+/// Windows scripts get CRLF line endings and Unix scripts LF. On Unix hosts
+/// a `.sh` file written for a Unix target is also made executable, so
+/// converted scripts can be run directly.
+pub fn write_script(path: &std::path::Path, contents: &str, to_os: Os) -> std::io::Result<()> {
+    let lines = contents.lines();
+    let mut output = if to_os == Os::Windows {
+        lines.collect::<Vec<_>>().join("\r\n")
+    } else {
+        lines.collect::<Vec<_>>().join("\n")
+    };
+    if contents.ends_with('\n') {
+        output.push_str(if to_os == Os::Windows { "\r\n" } else { "\n" });
+    }
+    std::fs::write(path, output)?;
+
+    #[cfg(unix)]
+    if to_os.is_unix_like() && path.extension().is_some_and(|ext| ext == "sh") {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut permissions = std::fs::metadata(path)?.permissions();
+        permissions.set_mode(permissions.mode() | 0o111);
+        std::fs::set_permissions(path, permissions)?;
+    }
+
+    Ok(())
+}
+
+/// A translated script header line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShebangTranslation {
+    /// The new first line of the script
+    pub line: String,
+    /// Why the line changed, or why it may not work on the target
+    pub warning: Option<String>,
+}
+
+/// Translate a shebang line from a script
+///
+/// # Arguments
+///
+/// * `line` - The shebang line (e.g., "#!/bin/bash")
+/// * `from_os` - The source operating system  
+/// * `to_os` - The target operating system
+///
+/// # Returns
+///
+/// The translated shebang or equivalent for target OS. See
+/// [`translate_shebang_line`] for the rules and the warning that goes with them.
+pub fn translate_shebang(line: &str, from_os: Os, to_os: Os) -> String {
+    translate_shebang_line(line, from_os, to_os).line
+}
+
+/// Translate a shebang line, explaining what changed
+///
+/// - Shell shebangs (`#!/bin/bash`, `#!/usr/bin/env sh`) become `@echo off`
+///   for Windows targets.
+/// - Other interpreters (`#!/usr/bin/env python3`) are kept: Windows has no
+///   shebangs, but launchers such as `py.exe` read them.
+/// - A batch `@echo off` becomes `#!/bin/bash` for Unix targets.
+///
+/// Anything else, and every line between OSes of the same family, is
+/// returned unchanged without a warning.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_shebang_line, Os};
+///
+/// let result = translate_shebang_line("#!/usr/bin/env bash", Os::Linux, Os::Windows);
+/// assert_eq!(result.line, "@echo off");
+/// assert!(result.warning.is_some());
+/// ```
+pub fn translate_shebang_line(line: &str, from_os: Os, to_os: Os) -> ShebangTranslation {
+    let unchanged = |line: &str| ShebangTranslation { line: line.to_string(), warning: None };
+    if from_os == to_os {
+        return unchanged(line);
+    }
+
+    let line = line.trim();
+
+    // Unix to Windows - shells are replaced by a batch header, other interpreters kept
+    if from_os.is_unix_like() && to_os == Os::Windows {
+        let Some(interpreter) = shebang_interpreter(line) else {
+            return unchanged(line);
+        };
+        if matches!(interpreter, "sh" | "bash" | "dash" | "zsh" | "ksh") {
+            return ShebangTranslation {
+                line: "@echo off".to_string(),
+                warning: Some(format!("{} shebang replaced by @echo off; the script runs in cmd.exe", interpreter)),
+            };
+        }
+        return ShebangTranslation {
+            line: line.to_string(),
+            warning: Some(format!(
+                "{} shebang kept; Windows only honors it through a launcher such as py.exe",
+                interpreter
+            )),
+        };
+    }
+
+    // Windows to Unix - convert @echo off to shebang
+    if from_os == Os::Windows && to_os.is_unix_like() && line.to_lowercase().starts_with("@echo off") {
+        return ShebangTranslation {
+            line: "#!/bin/bash".to_string(),
+            warning: Some("@echo off replaced by a bash shebang; Unix shells don't echo commands by default".to_string()),
+        };
+    }
+
+    unchanged(line)
+}
+
+/// The interpreter named by a shebang, looking through `/usr/bin/env`
+fn shebang_interpreter(line: &str) -> Option<&str> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let program = words.next()?;
+    let name = program.rsplit('/').next().unwrap_or(program);
+    if name == "env" {
+        // `env -S` splits the rest of the line; skip options to reach the program
+        words.find(|w| !w.starts_with('-'))
+    } else {
+        Some(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let (cmd, args) = parse_command("ls -la /home");
+        assert_eq!(cmd, "ls");
+        assert_eq!(args, vec!["-la", "/home"]);
+    }
+
+    #[test]
+    fn test_parse_command_no_args() {
+        let (cmd, args) = parse_command("ls");
+        assert_eq!(cmd, "ls");
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_command_empty() {
+        let (cmd, args) = parse_command("");
+        assert!(cmd.is_empty());
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_command_quoted_args() {
+        let (cmd, args) = parse_command("start \"\" \"C:\\Program Files\\app.exe\"");
+        assert_eq!(cmd, "start");
+        assert_eq!(args, vec!["\"\"", "\"C:\\Program Files\\app.exe\""]);
+    }
+
+    #[test]
+    fn test_quoted_arguments_stay_whole() {
+        // Quoted arguments with spaces are single tokens for every command
+        let (_, args) = parse_command("findstr \"two words\" notes.txt");
+        assert_eq!(args, vec!["\"two words\"", "notes.txt"]);
+
+        let result = translate_command("findstr \"two words\" notes.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "grep \"two words\" notes.txt");
+
+        let result = translate_command("del \"old file.txt\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "rm \"old file.txt\"");
+
+        let result = translate_full("copy \"C:\\My Docs\\a b.txt\" D:\\backup", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cp \"/mnt/c/My Docs/a b.txt\" /mnt/d/backup");
+    }
+
+    #[test]
+    fn test_translate_dir_to_ls() {
+        let result = translate_command("dir", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "ls");
+    }
+
+    #[test]
+    fn test_translate_dir_with_flags() {
+        let result = translate_command("dir /w", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ls"));
+        assert!(result.command.contains("-C"));
+    }
+
+    #[test]
+    fn test_translate_ls_to_dir() {
+        let result = translate_command("ls", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "dir");
+    }
+
+    #[test]
+    fn test_translate_ls_with_flags() {
+        let result = translate_command("ls -la", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("dir"));
+    }
+
+    #[test]
+    fn test_translate_copy_to_cp() {
+        let result = translate_command("copy /y", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("cp"));
+        assert!(result.command.contains("-f"));
+    }
+
+    #[test]
+    fn test_translate_cls_to_clear() {
+        let result = translate_command("cls", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "clear");
+    }
+
+    #[test]
+    fn test_translate_clear_to_cls() {
+        let result = translate_command("clear", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "cls");
+    }
+
+    #[test]
+    fn test_translate_grep_to_findstr() {
+        let result = translate_command("grep -i pattern", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("findstr"));
+        assert!(result.command.contains("/i"));
+    }
+
+    #[test]
+    fn test_translate_findstr_to_grep() {
+        let result = translate_command("findstr /i pattern", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("grep"));
+        assert!(result.command.contains("-i"));
+    }
+
+    #[test]
+    fn test_translate_same_os() {
+        let result = translate_command("ls -la", Os::Linux, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "ls -la");
+    }
+
+    #[test]
+    fn test_translate_empty_command() {
+        let result = translate_command("", Os::Windows, Os::Linux);
+        assert!(result.is_err());
+        match result {
+            Err(TranslationError::EmptyCommand) => {}
+            _ => panic!("Expected EmptyCommand error"),
+        }
+    }
+
+    #[test]
+    fn test_translate_command_not_found() {
+        let result = translate_command("nonexistent", Os::Windows, Os::Linux);
+        assert!(result.is_err());
+        match result {
+            Err(TranslationError::CommandNotFound(_)) => {}
+            _ => panic!("Expected CommandNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_translate_command_name() {
+        assert_eq!(translate_command_name("dir", Os::Windows, Os::Linux), Some("ls".to_string()));
+        assert_eq!(translate_command_name("DIR", Os::Windows, Os::Linux), Some("ls".to_string()));
+        assert_eq!(translate_command_name("md", Os::Windows, Os::Linux), Some("mkdir".to_string()));
+        assert_eq!(translate_command_name("grep", Os::Linux, Os::Windows), Some("findstr".to_string()));
+        assert_eq!(translate_command_name("ls", Os::Windows, Os::Linux), Some("ls".to_string()));
+        assert_eq!(translate_command_name("htop", Os::Linux, Os::MacOS), Some("htop".to_string()));
+        assert_eq!(translate_command_name("nonexistent", Os::Windows, Os::Linux), None);
+        assert_eq!(translate_command_name("", Os::Windows, Os::Linux), None);
+    }
+
+    #[test]
+    fn test_translate_command_str() {
+        let result = translate_command_str("dir", "windows", "linux");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().command, "ls");
+    }
+
+    #[test]
+    fn test_translate_command_str_invalid_os() {
+        let result = translate_command_str("dir", "invalid", "linux");
+        assert!(result.is_err());
+        match result {
+            Err(TranslationError::InvalidOs(_)) => {}
+            _ => panic!("Expected InvalidOs error"),
+        }
+    }
+
+    #[test]
+    fn test_translate_batch() {
+        let commands = vec!["dir", "cls", "copy"];
+        let results = translate_batch(&commands, Os::Windows, Os::Linux);
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn test_translate_batch_iter_is_lazy() {
+        let pulled = std::cell::Cell::new(0);
+        let commands = ["dir", "cls", "copy", "type"].into_iter().inspect(|_| pulled.set(pulled.get() + 1));
+
+        let mut results = translate_batch_iter(commands, Os::Windows, Os::Linux);
+        assert_eq!(pulled.get(), 0);
+
+        assert_eq!(results.next().unwrap().unwrap().command, "ls");
+        assert_eq!(pulled.get(), 1);
+
+        let rest: Vec<_> = results.take(1).collect();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(pulled.get(), 2);
+    }
+
+    #[test]
+    fn test_detect_source_os() {
+        assert_eq!(detect_source_os("dir /w"), Some(Os::Windows));
+        assert_eq!(detect_source_os("ipconfig /all"), Some(Os::Windows));
+        assert_eq!(detect_source_os("grep -i foo"), Some(Os::Linux));
+        assert_eq!(detect_source_os("open file.txt"), Some(Os::MacOS));
+        assert_eq!(detect_source_os("ping C:\\hosts.txt"), Some(Os::Windows));
+        assert_eq!(detect_source_os("ping /etc/hosts"), Some(Os::Linux));
+        assert_eq!(detect_source_os("echo hello"), None);
+        assert_eq!(detect_source_os(""), None);
+    }
+
+    #[test]
+    fn test_unix_to_unix_passthrough() {
+        let result = translate_command("some_unix_cmd", Os::Linux, Os::MacOS);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "some_unix_cmd");
+    }
+
+    #[test]
+    fn test_translate_tasklist_to_ps() {
+        let result = translate_command("tasklist", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ps"));
+    }
+
+    #[test]
+    fn test_translate_ps_to_tasklist() {
+        let result = translate_command("ps", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("tasklist"));
+    }
+
+    #[test]
+    fn test_translate_ping_flags() {
+        let result = translate_command("ping -n 5 localhost", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ping"));
+        assert!(result.command.contains("-c"));
+    }
+
+    #[test]
+    fn test_compound_command_and() {
+        let result = translate_compound_command("dir && cls", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ls"));
+        assert!(result.command.contains("&&"));
+        assert!(result.command.contains("clear"));
+    }
+
+    #[test]
+    fn test_compound_command_or() {
+        let result = translate_compound_command("dir || cls", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ls"));
+        assert!(result.command.contains("||"));
+        assert!(result.command.contains("clear"));
+    }
+
+    #[test]
+    fn test_compound_command_pipe() {
+        let result = translate_compound_command("dir | findstr test", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ls"));
+        assert!(result.command.contains("|"));
+        assert!(result.command.contains("grep"));
+    }
+
+    #[test]
+    fn test_compound_command_semicolon() {
+        let result = translate_compound_command("ls; clear", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("dir"));
+        // cmd.exe separates statements with &, not ;
+        assert!(result.command.contains("&"));
+        assert!(!result.command.contains(";"));
+        assert!(result.command.contains("cls"));
+    }
+
+    #[test]
+    fn test_compound_command_single() {
+        let result = translate_compound_command("dir", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().command, "ls");
+    }
+
+    #[test]
+    fn test_split_compound_command() {
+        let parts = split_compound_command("dir && cls || type");
+        assert_eq!(parts.len(), 5);
+        assert_eq!(parts[0].trim(), "dir");
+        assert_eq!(parts[1], "&&");
+        assert_eq!(parts[2].trim(), "cls");
+        assert_eq!(parts[3], "||");
+        assert_eq!(parts[4].trim(), "type");
+    }
+
+    #[test]
+    fn test_compound_windows_semicolon_is_not_a_separator() {
+        assert_eq!(split_compound_command_for("set PATH=%PATH%;C:\\bin", Os::Windows).len(), 1);
+        assert_eq!(split_compound_command_for("ls; pwd", Os::Linux).len(), 3);
+
+        let result = translate_compound_command("set PATH=%PATH%;C:\\bin", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export PATH=\"$PATH:/mnt/c/bin\"");
+
+        let result = translate_compound_command("set PATH=%PATH%;C:\\bin && cls", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export PATH=\"$PATH:/mnt/c/bin\" && clear");
+    }
+
+    #[test]
+    fn test_compound_operators_match_splitter() {
+        let ops = compound_operators();
+        assert_eq!(ops, COMPOUND_OPERATORS);
+        for op in ops {
+            let parts = split_compound_command(&format!("dir {} cls", op));
+            assert_eq!(parts.len(), 3);
+            assert_eq!(parts[1], *op);
+        }
+    }
+
+    #[test]
+    fn test_native_command_passthrough() {
+        // If we're translating from Linux to Windows, but the command is already
+        // a Windows command (like 'dir'), it should pass through unchanged
+        let result = translate_command("dir", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "dir");
+        assert!(result.warnings.iter().any(|w| w.contains("already")));
+    }
+
+    #[test]
+    fn test_native_command_passthrough_with_flags() {
+        // Windows command with Windows flags should pass through
+        let result = translate_command("dir /w", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "dir /w");
+    }
+
+    #[test]
+    fn test_native_unix_command_passthrough_to_linux() {
+        // If we're translating from Windows to Linux, but the command is already
+        // a Linux command (like 'ls'), it should pass through unchanged
+        let result = translate_command("ls", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "ls");
+        assert!(result.warnings.iter().any(|w| w.contains("already")));
+    }
+
+    #[test]
+    fn test_native_unix_command_passthrough_with_flags() {
+        // Unix command with Unix flags should pass through
+        let result = translate_command("ls -la", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.command, "ls -la");
+    }
+
+    #[test]
+    fn test_common_command_with_different_flags() {
+        // ping exists on both OSes but has different flag syntax
+        // When translating from Windows to Linux, flags should be translated
+        let result = translate_command("ping -n 5 localhost", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.command.contains("ping"));
+        assert!(result.command.contains("-c")); // -n becomes -c
+    }
+
+    #[test]
+    fn test_translate_script_extension_bat_to_sh() {
+        let result = translate_script_extension("script.bat", Os::Windows, Os::Linux);
+        assert_eq!(result, "script.sh");
+    }
+
+    #[test]
+    fn test_translate_script_extension_cmd_to_sh() {
+        let result = translate_script_extension("build.cmd", Os::Windows, Os::Linux);
+        assert_eq!(result, "build.sh");
+    }
+
+    #[test]
+    fn test_translate_script_extension_ps1_to_sh() {
+        let result = translate_script_extension("deploy.ps1", Os::Windows, Os::Linux);
+        assert_eq!(result, "deploy.sh");
+    }
+
+    #[test]
+    fn test_translate_script_extension_sh_to_bat() {
+        let result = translate_script_extension("script.sh", Os::Linux, Os::Windows);
+        assert_eq!(result, "script.bat");
+    }
+
+    #[test]
+    fn test_translate_script_extension_exe_removal() {
+        let result = translate_script_extension("program.exe", Os::Windows, Os::Linux);
+        assert_eq!(result, "program");
+    }
+
+    #[test]
+    fn test_translate_script_extension_add_exe() {
+        let result = translate_script_extension("program", Os::Linux, Os::Windows);
+        assert_eq!(result, "program.exe");
+    }
+
+    #[test]
+    fn test_translate_script_extension_same_os() {
+        let result = translate_script_extension("script.bat", Os::Windows, Os::Windows);
+        assert_eq!(result, "script.bat");
+    }
+
+    #[test]
+    fn test_translate_script_filename() {
+        let cases = [
+            ("install.bat", Os::Windows, Os::Linux, "install.sh"),
+            ("C:\\tools\\build.CMD", Os::Windows, Os::Linux, "C:\\tools\\build.sh"),
+            ("deploy.ps1", Os::Windows, Os::MacOS, "deploy.sh"),
+            ("./scripts/setup.sh", Os::Linux, Os::Windows, "./scripts/setup.bat"),
+            ("bin/run", Os::Linux, Os::Windows, "bin/run.exe"),
+            ("Makefile", Os::Windows, Os::Linux, "Makefile"),
+        ];
+        for (filename, from_os, to_os, expected) in cases {
+            let result = translate_script_filename(filename, from_os, to_os);
+            assert_eq!(result.name, expected, "{}", filename);
+            assert_eq!(result.warning, None, "{}", filename);
+        }
+
+        let result = translate_script_filename("v1.2/tool.py", Os::Linux, Os::Windows);
+        assert_eq!(result.name, "v1.2/tool.py");
+        assert!(result.warning.unwrap().contains("'.py'"));
+        // Dotfiles have no extension to swap
+        assert_eq!(translate_script_filename(".profile", Os::Linux, Os::Windows).warning, None);
+    }
+
+    #[test]
+    fn test_translate_shebang_unix_to_windows() {
+        let result = translate_shebang("#!/bin/bash", Os::Linux, Os::Windows);
+        assert_eq!(result, "@echo off");
+    }
+
+    #[test]
+    fn test_translate_shebang_windows_to_unix() {
+        let result = translate_shebang("@echo off", Os::Windows, Os::Linux);
+        assert_eq!(result, "#!/bin/bash");
+    }
+
+    #[test]
+    fn test_translate_shebang_line_bash_to_windows() {
+        let result = translate_shebang_line("#!/bin/bash", Os::Linux, Os::Windows);
+        assert_eq!(result.line, "@echo off");
+        assert!(result.warning.unwrap().contains("bash shebang replaced"));
+
+        let result = translate_shebang_line("#!/usr/bin/env -S bash -e", Os::Linux, Os::Windows);
+        assert_eq!(result.line, "@echo off");
+    }
+
+    #[test]
+    fn test_translate_shebang_line_keeps_env_interpreters() {
+        let result = translate_shebang_line("#!/usr/bin/env python3", Os::Linux, Os::Windows);
+        assert_eq!(result.line, "#!/usr/bin/env python3");
+        assert!(result.warning.unwrap().contains("py.exe"));
+
+        let result = translate_shebang_line("#!/usr/bin/env python3", Os::Linux, Os::MacOS);
+        assert_eq!(result.line, "#!/usr/bin/env python3");
+        assert!(result.warning.is_none());
+    }
+
+    #[test]
+    fn test_translate_shebang_line_windows_to_unix() {
+        let result = translate_shebang_line("@ECHO OFF", Os::Windows, Os::Linux);
+        assert_eq!(result.line, "#!/bin/bash");
+        assert!(result.warning.is_some());
+
+        let result = translate_shebang_line("dir", Os::Windows, Os::Linux);
+        assert_eq!(result, ShebangTranslation { line: "dir".to_string(), warning: None });
+    }
+
+    #[test]
+    fn test_translate_shebang_same_os() {
+        let result = translate_shebang("#!/bin/bash", Os::Linux, Os::Linux);
+        assert_eq!(result, "#!/bin/bash");
+    }
+
+    #[test]
+    fn test_translate_full_windows_to_linux_with_path() {
+        let result = translate_full("copy C:\\Users\\file.txt D:\\backup\\", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let r = result.unwrap();
+        assert!(r.command.contains("cp"));
+        assert!(r.command.contains("/mnt/c/"));
+        assert!(r.command.contains("/mnt/d/"));
+    }
+
+    #[test]
+    fn test_translate_full_linux_to_windows_with_path() {
+        let result = translate_full("cp /mnt/c/Users/file.txt /tmp/backup", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let r = result.unwrap();
+        assert!(r.command.contains("copy"));
+        assert!(r.command.contains("C:"));
+    }
+
+    #[test]
+    fn test_translate_full_dir_with_path() {
+        let result = translate_full("dir C:\\Windows", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let r = result.unwrap();
+        assert!(r.command.contains("ls"));
+        assert!(r.command.contains("/mnt/c/"));
+    }
+
+    #[test]
+    fn test_translate_full_ls_with_path() {
+        let result = translate_full("ls /home/user/documents", Os::Linux, Os::Windows);
+        assert!(result.is_ok());
+        let r = result.unwrap();
+        assert!(r.command.contains("dir"));
+        assert!(r.command.contains("Users"));
+    }
+
+    #[test]
+    fn test_translate_full_preserves_flags_and_paths() {
+        let result = translate_full("copy /y C:\\src\\file.txt D:\\dest\\", Os::Windows, Os::Linux);
+        assert!(result.is_ok());
+        let r = result.unwrap();
+        assert!(r.command.contains("cp"));
+        assert!(r.command.contains("-f")); // /y -> -f
+        assert!(r.command.contains("/mnt/c/"));
+        assert!(r.command.contains("/mnt/d/"));
+    }
+
+    #[test]
+    fn test_is_path_argument() {
+        // Windows paths
+        assert!(is_path_argument("C:\\Users", Os::Windows));
+        assert!(is_path_argument("D:\\Documents\\file.txt", Os::Windows));
+        
+        // Unix paths
+        assert!(is_path_argument("/home/user", Os::Linux));
+        assert!(is_path_argument("~/Documents", Os::Linux));
+        assert!(is_path_argument("./local/file", Os::Linux));
+        
+        // Not paths (flags)
+        assert!(!is_path_argument("-la", Os::Linux));
+        assert!(!is_path_argument("/w", Os::Windows));
+        assert!(!is_path_argument("--help", Os::Linux));
+    }
+
+    #[test]
+    fn test_translate_echo_n_to_windows() {
+        let result = translate_command("echo -n hi", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo | set /p=hi");
+        assert!(result.warnings.iter().any(|w| w.contains("set /p")));
+    }
+
+    #[test]
+    fn test_translate_echo_e_to_windows() {
+        let result = translate_command("echo -e \"a\\tb\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo \"a\\tb\"");
+        assert!(result.warnings.iter().any(|w| w.contains("-e")));
+    }
+
+    #[test]
+    fn test_translate_echo_plain_to_windows() {
+        let result = translate_command("echo hello", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo hello");
+        assert!(result.warnings.is_empty());
+
+        let result = translate_command("echo", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo.");
+    }
+
+    #[test]
+    fn test_translate_start_drops_empty_title() {
+        let result = translate_full("start \"\" \"C:\\x.txt\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "xdg-open /mnt/c/x.txt");
+    }
+
+    #[test]
+    fn test_translate_start_drops_title() {
+        let result = translate_full("start \"My Window\" C:\\docs\\a.pdf", Os::Windows, Os::MacOS).unwrap();
+        assert_eq!(result.command, "open /mnt/c/docs/a.pdf");
+        assert!(result.warnings.iter().any(|w| w.contains("title")));
+    }
+
+    #[test]
+    fn test_translate_start_without_title() {
+        let result = translate_command("start notepad", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "xdg-open notepad");
+    }
+
+    #[test]
+    fn test_translate_full_keeps_quotes_for_paths_with_spaces() {
+        let result = translate_full("type \"C:\\My Docs\\a.txt\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cat \"/mnt/c/My Docs/a.txt\"");
+    }
+
+    #[test]
+    fn test_translate_export_path_prepend_to_windows() {
+        let result = translate_command("export PATH=\"/opt/bin:$PATH\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set PATH=C:\\opt\\bin;%PATH%");
+    }
+
+    #[test]
+    fn test_translate_export_path_append_to_windows() {
+        let result = translate_command("export PATH=\"$PATH:/opt/bin\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set PATH=%PATH%;C:\\opt\\bin");
+    }
+
+    #[test]
+    fn test_translate_set_path_prepend_to_linux() {
+        let result = translate_full("set PATH=C:\\tools;%PATH%", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export PATH=\"/mnt/c/tools:$PATH\"");
+    }
+
+    #[test]
+    fn test_translate_set_path_append_to_linux() {
+        let result = translate_full("set PATH=%PATH%;C:\\tools;D:\\bin", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export PATH=\"$PATH:/mnt/c/tools:/mnt/d/bin\"");
+    }
+
+    #[test]
+    fn test_translate_set_other_variable_unchanged() {
+        // Only PATH gets entry-order handling; other values are kept as written
+        let result = translate_command("set FOO=bar", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export FOO=bar");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_bare_set_lists_variables() {
+        let result = translate_command("set", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "env");
+    }
+
+    #[test]
+    fn test_translate_rm_end_of_options() {
+        let result = translate_command("rm -- -x.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del -x.txt");
+        assert!(!result.had_unmapped_flags);
+    }
+
+    #[test]
+    fn test_translate_rm_rf_end_of_options() {
+        let result = translate_command("rm -rf -- -dir", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /s /q /f -dir");
+        assert!(!result.had_unmapped_flags);
+    }
+
+    #[test]
+    fn test_translate_end_of_options_kept_for_unix_target() {
+        let result = translate_command("del /q -- /s", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "rm -f -- /s");
+    }
+
+    #[test]
+    fn test_translate_grep_quiet() {
+        let result = translate_command("grep -q pattern file.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr pattern file.txt >nul");
+        assert!(result.warnings.iter().any(|w| w.contains("-q")));
+    }
+
+    #[test]
+    fn test_translate_grep_quiet_bundled() {
+        let result = translate_command("grep -qi foo file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /i foo file >nul");
+        assert!(result.warnings.iter().any(|w| w.contains("-q")));
+        assert_eq!(result.confidence, Confidence::Approximate);
+
+        let result = translate_command("grep -iq foo file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /i foo file >nul");
+    }
+
+    #[test]
+    fn test_translate_grep_quiet_in_compound() {
+        let result = translate_compound_command(
+            "grep -q -i pattern file.txt && echo found",
+            Os::Linux,
+            Os::Windows,
+        )
+        .unwrap();
+        assert_eq!(result.command, "findstr /i pattern file.txt >nul && echo found");
+    }
+
+    #[test]
+    fn test_unsupported_control_flow() {
+        for input in ["if [ -f x ]", "while true", "for f in *.txt", "case $x in"] {
+            match translate_command(input, Os::Linux, Os::Windows) {
+                Err(TranslationError::UnsupportedConstruct(c)) => assert!(c.contains("control flow")),
+                other => panic!("Expected UnsupportedConstruct for '{}', got {:?}", input, other),
+            }
+        }
+        match translate_command("goto end", Os::Windows, Os::Linux) {
+            Err(TranslationError::UnsupportedConstruct(c)) => assert!(c.contains("goto")),
+            other => panic!("Expected UnsupportedConstruct, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_heredoc() {
+        match translate_command("cat <<EOF", Os::Linux, Os::Windows) {
+            Err(TranslationError::UnsupportedConstruct(c)) => assert_eq!(c, "heredoc"),
+            other => panic!("Expected UnsupportedConstruct, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_subshell() {
+        match translate_full("(cd /tmp)", Os::Linux, Os::Windows) {
+            Err(TranslationError::UnsupportedConstruct(c)) => assert_eq!(c, "subshell"),
+            other => panic!("Expected UnsupportedConstruct, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_command_substitution() {
+        for input in ["echo $(date)", "echo `date`"] {
+            match translate_command(input, Os::Linux, Os::Windows) {
+                Err(TranslationError::UnsupportedConstruct(c)) => assert_eq!(c, "command substitution"),
+                other => panic!("Expected UnsupportedConstruct for '{}', got {:?}", input, other),
+            }
+        }
+        // Single-quoted text is literal
+        assert!(translate_command("echo '$(date)'", Os::Linux, Os::Windows).is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_construct_allowed_between_unix_systems() {
+        let result = translate_command("echo $(date)", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "echo $(date)");
+    }
+
+    #[test]
+    fn test_unsupported_construct_in_compound_is_warning() {
+        let result = translate_compound_command("ls && cat <<EOF", Os::Linux, Os::Windows).unwrap();
+        assert!(result.had_unsupported_constructs);
+        assert_eq!(result.command, "dir && cat <<EOF");
+        assert!(result.warnings.iter().any(|w| w.contains("Unsupported shell construct")));
+    }
+
+    #[test]
+    fn test_translate_ls_color_linux_to_macos() {
+        let result = translate_command("ls --color=auto", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "ls -G");
+
+        let result = translate_command("ls -la --color=auto", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "ls -la -G");
+        assert!(!result.had_unmapped_flags);
+    }
+
+    #[test]
+    fn test_translate_ls_color_macos_to_linux() {
+        let result = translate_command("ls -G", Os::MacOS, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls --color=auto");
+    }
+
+    #[test]
+    fn test_translate_ls_color_dropped_for_windows() {
+        let result = translate_command("ls --color=always", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir");
+
+        let result = translate_command("ls -G -F", Os::MacOS, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir");
+    }
+
+    #[test]
+    fn test_translate_collapses_whitespace_by_default() {
+        let result = translate_command("echo    aligned   text", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo aligned text");
+    }
+
+    #[test]
+    fn test_translate_preserve_whitespace() {
+        let options = TranslationOptions { preserve_whitespace: true, ..Default::default() };
+
+        let result = translate_command_with_options("echo    aligned   text", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "echo aligned   text");
+
+        let result = translate_command_with_options("findstr /i   foo\tbar   file.txt", Os::Windows, Os::Linux, &options).unwrap();
+        assert_eq!(result.command, "grep -i foo\tbar   file.txt");
+
+        let result = translate_command_with_options("echo \"a   b\"   c", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "echo \"a   b\"   c");
+    }
+
+    #[test]
+    fn test_translate_preserve_whitespace_without_operands() {
+        let options = TranslationOptions { preserve_whitespace: true, ..Default::default() };
+        let result = translate_command_with_options("dir   /w", Os::Windows, Os::Linux, &options).unwrap();
+        assert_eq!(result.command, "ls -C");
+    }
+
+    #[test]
+    fn test_translate_preserve_whitespace_with_minimal_coreutils() {
+        let options = TranslationOptions { preserve_whitespace: true, minimal_coreutils: true, ..Default::default() };
+        let result =
+            translate_command_with_options("grep --color=auto -i   foo   bar.txt", Os::Linux, Os::Linux, &options).unwrap();
+        assert_eq!(result.command, "grep -i foo   bar.txt");
+        assert!(result.warnings.iter().any(|w| w.contains("BusyBox")));
+    }
+
+    #[test]
+    fn test_translate_chmod_recursive() {
+        let result = translate_command("chmod -R +w dir", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "attrib -R /s dir");
+        assert!(result.warnings.iter().any(|w| w.contains("permissions")));
+    }
+
+    #[test]
+    fn test_translate_chmod_modes() {
+        let result = translate_command("chmod -w file.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "attrib +R file.txt");
+
+        let result = translate_command("chmod 755 script.sh", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "attrib -R script.sh");
+
+        let result = translate_command("chmod 444 file.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "attrib +R file.txt");
+
+        let result = translate_command("chmod +x run.sh", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "attrib run.sh");
+        assert!(result.warnings.iter().any(|w| w.contains("no attrib equivalent")));
+    }
+
+    #[test]
+    fn test_compound_already_translated_passthrough() {
+        let result = translate_compound_command("ls -la && clear", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -la && clear");
+        assert!(result.warnings.iter().any(|w| w.contains("already translated")));
+
+        let result = translate_compound_command("dir /w | findstr foo", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir /w | findstr foo");
+    }
+
+    #[test]
+    fn test_compound_already_translated_translates_operators() {
+        let result = translate_compound_command("dir ; cls", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir & cls");
+
+        let result = translate_compound_command("ls -la & clear", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -la ; clear");
+
+        let result = translate_compound_command("dir ;; cls", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir & cls");
+        assert!(result.warnings.iter().any(|w| w.contains("Empty command segments")));
+    }
+
+    #[test]
+    fn test_script_mixing_source_and_target_lines() {
+        let script = ["dir /w && cls", "ls -la && clear", "type a.txt | findstr x", "cat a.txt | grep x"];
+        let translated: Vec<String> = script
+            .iter()
+            .map(|line| translate_compound_command(line, Os::Windows, Os::Linux).unwrap().command)
+            .collect();
+        assert_eq!(
+            translated,
+            vec!["ls -C && clear", "ls -la && clear", "cat a.txt | grep x", "cat a.txt | grep x"]
+        );
+    }
+
+    #[test]
+    fn test_compound_shared_command_with_mapping_is_translated() {
+        // ping exists on both, so its flags still need translating
+        let result = translate_compound_command("ls && ping -n 5 host", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls && ping -c 5 host");
+    }
+
+    #[test]
+    fn test_grep_fixed_strings_to_findstr_literal() {
+        let result = translate_command("grep -F 'a.b' file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /l a.b file");
+        let result = translate_command("grep --fixed-strings a.b file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /l a.b file");
+    }
+
+    #[test]
+    fn test_findstr_literal_to_grep_fixed_strings() {
+        let result = translate_command("findstr /l a.b file", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "grep -F a.b file");
+    }
+
+    #[test]
+    fn test_grep_pattern_with_cmd_metacharacters_stays_quoted() {
+        let result = translate_command("grep 'a>b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr \"a>b\" f");
+        let result = translate_command("grep -F 'a>b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /l \"a>b\" f");
+        let result = translate_command("grep 'a&b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr \"a&b\" f");
+    }
+
+    #[test]
+    fn test_grep_extended_alternation_to_findstr_pattern_list() {
+        let result = translate_command("grep -E 'a|b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /r \"a b\" f");
+
+        let result = translate_command("grep -E '(a|b)c' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /r \"(a|b)c\" f");
+        assert!(result.warnings.iter().any(|w| w.contains("Alternation")));
+    }
+
+    #[test]
+    fn test_grep_regexp_pattern_with_cmd_metacharacters_stays_quoted() {
+        let result = translate_command("grep -e 'a|b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr \"a|b\" f");
+        let result = translate_command("grep --regexp='a>b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr \"a>b\" f");
+        let result = translate_command("grep -E -e 'a|b' f", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /r \"a b\" f");
+    }
+
+    #[test]
+    fn test_compound_semicolon_to_windows_ampersand() {
+        let result = translate_compound_command("ls; clear", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir & cls");
+    }
+
+    #[test]
+    fn test_compound_ampersand_to_unix_semicolon() {
+        let result = translate_compound_command("dir & cls", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls ; clear");
+    }
+
+    #[test]
+    fn test_split_compound_respects_quotes_and_redirects() {
+        let parts = split_compound_command("echo \"a;b & c\" 2>&1");
+        assert_eq!(parts, vec!["echo \"a;b & c\" 2>&1"]);
+        let parts = split_compound_command("make &> build.log");
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn test_df_powershell_mode_uses_get_psdrive() {
+        let options = TranslationOptions { shell: Some(Shell::PowerShell), ..Default::default() };
+        let result = translate_command_with_options("df", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "Get-PSDrive -PSProvider FileSystem");
+
+        // Unix flags have no meaning to the cmdlet
+        let result = translate_command_with_options("df -h", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "Get-PSDrive -PSProvider FileSystem");
+        assert!(result.warnings.iter().any(|w| w.contains("'-h'")));
+
+        // cmd.exe stays on the wmic form
+        let result = translate_command_with_options("df", Os::Linux, Os::Windows, &TranslationOptions::default()).unwrap();
+        assert!(result.command.starts_with("wmic logicaldisk"));
+    }
+
+    #[test]
+    fn test_mixed_pipeline_segments_analyzed_independently() {
+        let result = translate_compound_command("ls | findstr x", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls | grep x");
+
+        // find exists on both; its Unix flags mark it as already translated
+        let result = translate_compound_command("find . -name x | findstr /i y", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "find . -name x | grep -i y");
+
+        let result = translate_compound_command("dir | sort /r", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls | sort -r");
+    }
+
+    #[test]
+    fn test_mixed_pipeline_to_windows() {
+        let result = translate_compound_command("type a.txt | grep -i x", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "type a.txt | findstr /i x");
+    }
+
+    #[test]
+    fn test_grep_count_pipes_into_find() {
+        let result = translate_command("grep -c error log.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr error log.txt | find /c /v \"\"");
+        assert!(!result.command.contains("/c:"));
+    }
+
+    #[test]
+    fn test_grep_count_bundled() {
+        for input in ["grep -ic foo file", "grep -ci foo file"] {
+            let result = translate_command(input, Os::Linux, Os::Windows).unwrap();
+            assert_eq!(result.command, "findstr /i foo file | find /c /v \"\"", "{}", input);
+            assert!(!result.command.contains("-c"));
+        }
+    }
+
+    #[test]
+    fn test_prefix_assignment_to_windows_set_and_clear() {
+        let result = translate_command("DEBUG=1 ./run.sh", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set \"DEBUG=1\" & call run.bat & set \"DEBUG=\"");
+        assert!(result.warnings.iter().any(|w| w.contains("Command-scoped")));
+        assert!(!result.warnings.iter().any(|w| w.contains("not translated")));
+        let result = translate_command("./run.sh --fast", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "call run.bat --fast");
+
+        // The wrapped command's flags are translated and its warnings kept
+        let result = translate_command("DEBUG=1 ls -la -Z", Os::Linux, Os::Windows).unwrap();
+        assert!(result.command.starts_with("set \"DEBUG=1\" & dir /a"));
+        assert!(result.had_unmapped_flags);
+        assert!(result.warnings.iter().any(|w| w.contains("'-Z'")));
+
+        let result = translate_command("LANG=C LC_ALL=C ls -la", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(
+            result.command,
+            "set \"LANG=C\" & set \"LC_ALL=C\" & dir /a & set \"LANG=\" & set \"LC_ALL=\""
+        );
+    }
+
+    #[test]
+    fn test_assignment_only_line_to_windows() {
+        let result = translate_command("FOO=\"a b\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set FOO=a b");
+        assert!(!result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_grep_non_ascii_pattern_warns_about_encoding() {
+        let result = translate_command("grep café menu.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr café menu.txt");
+        assert!(result.warnings.iter().any(|w| w.contains("non-ASCII")));
+
+        let result = translate_command("grep cafe menu.txt", Os::Linux, Os::Windows).unwrap();
+        assert!(!result.warnings.iter().any(|w| w.contains("non-ASCII")));
+    }
+
+    #[test]
+    fn test_bundled_ls_flags_are_order_independent() {
+        for flags in ["-la", "-al", "-lah", "-hal"] {
+            let result = translate_command(&format!("ls {}", flags), Os::Linux, Os::Windows).unwrap();
+            assert_eq!(result.command, "dir /a", "ls {}", flags);
+            assert!(result.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_bundled_flags_keep_explicit_combined_entry() {
+        let result = translate_command("rm -rf build", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /s /q /f build");
+
+        // Without a combined entry each letter is mapped on its own
+        let result = translate_command("grep -rn x .", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /s /n x .");
+    }
+
+    #[test]
+    fn test_shell_escaped_for_unix() {
+        let result = translate_command("type \"my file.txt\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cat \"my file.txt\"");
+        assert_eq!(result.shell_escaped(Os::Linux), "'cat \"my file.txt\"'");
+
+        let result = TranslationResult::new("echo 'a b'".to_string(), String::new(), Os::Windows, Os::Linux);
+        assert_eq!(result.shell_escaped(Os::Linux), r"'echo '\''a b'\'''");
+    }
+
+    #[test]
+    fn test_shell_escaped_for_windows() {
+        let result = translate_command("cat \"my file.txt\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "type \"my file.txt\"");
+        assert_eq!(result.shell_escaped(Os::Windows), "\"type \"\"my file.txt\"\"\"");
+
+        let result = TranslationResult::new("echo %PATH%".to_string(), String::new(), Os::Linux, Os::Windows);
+        assert_eq!(result.shell_escaped(Os::Windows), "\"echo %%PATH%%\"");
+    }
+
+    #[test]
+    fn test_requires_privilege() {
+        let result = translate_command("mount /dev/sdb1 /mnt/usb", Os::Windows, Os::Linux).unwrap();
+        assert!(result.requires_privilege);
+        let result = translate_command("systemctl restart nginx", Os::Windows, Os::Linux).unwrap();
+        assert!(result.requires_privilege);
+
+        let result = translate_command("echo hello", Os::Linux, Os::Windows).unwrap();
+        assert!(!result.requires_privilege);
+        let result = translate_command("ls -la", Os::Linux, Os::Windows).unwrap();
+        assert!(!result.requires_privilege);
+    }
+
+    #[test]
+    fn test_compound_requires_privilege() {
+        let result = translate_compound_command("ls && chkdsk c:", Os::Linux, Os::Windows).unwrap();
+        assert!(result.requires_privilege);
+    }
+
+    #[test]
+    fn test_redirect_both_streams_preserved() {
+        let result = translate_command("dir /w > out.log 2>&1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -C > out.log 2>&1");
+
+        let result = translate_compound_command("dir /w > out.log 2>&1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -C > out.log 2>&1");
+
+        let result = translate_full("dir /w > C:\\logs\\out.log 2>&1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -C > /mnt/c/logs/out.log 2>&1");
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    }
+
+    #[test]
+    fn test_cd_drive_switch_to_unix() {
+        let result = translate_full("cd /d C:\\Temp", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cd /mnt/c/Temp");
+        let result = translate_full("cd C:\\Program Files", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cd \"/mnt/c/Program Files\"");
+        let result = translate_command("cd", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pwd");
+    }
+
+    #[test]
+    fn test_pushd_popd_path_translation() {
+        let result = translate_full("pushd D:\\src", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pushd /mnt/d/src");
+        let result = translate_command("popd", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "popd");
+    }
+
+    #[test]
+    fn test_cd_to_windows_adds_drive_switch() {
+        let result = translate_full("cd /mnt/d/x", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "cd /d D:\\x");
+        let result = translate_command("cd ..", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "cd ..");
+    }
+
+    #[test]
+    fn test_cmd_sequential_ampersand_to_unix() {
+        let result = translate_compound_command("dir & cls", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls ; clear");
+        let result = translate_compound_command("dir &", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls");
+    }
+
+    #[test]
+    fn test_bash_background_ampersand_to_start_b() {
+        let result = translate_compound_command("sleep 1 &", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "start /b sleep 1");
+        assert!(result.warnings.iter().any(|w| w.contains("start /b")));
+
+        let result = translate_compound_command("ping -c 5 host & ls", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "start /b ping -n 5 host & dir");
+    }
+
+    #[test]
+    fn test_grep_perl_regex_unsupported_for_cmd() {
+        let result = translate_command("grep -P '\\d+' file", Os::Linux, Os::Windows);
+        match result {
+            Err(TranslationError::UnsupportedConstruct(construct)) => assert!(construct.contains("grep -P")),
+            other => panic!("expected UnsupportedConstruct, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_grep_perl_regex_to_select_string() {
+        let options = TranslationOptions { shell: Some(Shell::PowerShell), ..Default::default() };
+        let result = translate_command_with_options("grep -P '\\d+' file", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "Select-String -Pattern '\\d+' -Path file -CaseSensitive");
+        assert!(result.warnings.iter().any(|w| w.contains(".NET regex")));
+
+        let result = translate_command_with_options("grep -Piv 'a+' f", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "Select-String -Pattern 'a+' -Path f -NotMatch");
+    }
+
+    #[test]
+    fn test_grep_recursive_to_select_string() {
+        let options = TranslationOptions { shell: Some(Shell::PowerShell), ..Default::default() };
+        let result = translate_command_with_options("grep -r TODO src", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "Get-ChildItem src -Recurse -File | Select-String -Pattern TODO -CaseSensitive");
+        assert!(result.warnings.is_empty());
+
+        let result = translate_command_with_options("grep -ri todo", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.command, "Get-ChildItem -Recurse -File | Select-String -Pattern todo");
+
+        // Without PowerShell the cmd.exe translation still applies
+        let result = translate_command("grep -r TODO src", Os::Linux, Os::Windows).unwrap();
+        assert!(result.command.starts_with("findstr"));
+    }
+
+    #[test]
+    fn test_command_name_as_argument_not_translated() {
+        // Only the first token is the command; `find` here is the search pattern
+        let result = translate_command("grep find file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr find file");
+        let result = translate_full("findstr find file", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "grep find file");
+        let result = translate_compound_command("type file | findstr dir", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cat file | grep dir");
+    }
+
+    #[test]
+    fn test_grep_multiple_e_patterns_combined() {
+        let result = translate_command("grep -e a -e b file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr \"a b\" file");
+
+        let result = translate_command("grep -i -e 'a b' -e c file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /i /c:\"a b\" /c:\"c\" file");
+    }
+
+    #[test]
+    fn test_grep_pattern_file_to_findstr_g() {
+        let result = translate_command("grep -f pats file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /g:pats file");
+
+        let result = translate_full("grep -f /tmp/pats.txt file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /g:C:\\tmp\\pats.txt file");
+    }
+
+    #[test]
+    fn test_recommended_shell_for_powershell_constructs() {
+        let options = TranslationOptions { shell: Some(Shell::PowerShell), ..Default::default() };
+        let result = translate_command_with_options("grep -P '\\d+' file", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.recommended_shell(), Shell::PowerShell);
+
+        let result = translate_command_with_options("df", Os::Linux, Os::Windows, &options).unwrap();
+        assert_eq!(result.recommended_shell(), Shell::PowerShell);
+
+        let result = TranslationResult::new("echo $env:PATH".to_string(), String::new(), Os::Linux, Os::Windows);
+        assert_eq!(result.recommended_shell(), Shell::PowerShell);
+    }
+
+    #[test]
+    fn test_recommended_shell_follows_target_os() {
+        let result = translate_command("dir /s", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.recommended_shell(), Shell::Sh);
+
+        let result = translate_command("ls -la | grep x", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.recommended_shell(), Shell::Cmd);
+
+        // Hyphenated tool names aren't cmdlets
+        let result = TranslationResult::new("add-apt-repository x".to_string(), String::new(), Os::Linux, Os::Windows);
+        assert_eq!(result.recommended_shell(), Shell::Cmd);
+    }
+
+    #[test]
+    fn test_nul_redirect_case_insensitive() {
+        for input in ["dir > NUL", "dir > nul", "dir > Nul"] {
+            let result = translate_command(input, Os::Windows, Os::Linux).unwrap();
+            assert_eq!(result.command, "ls > /dev/null");
+            assert_eq!(result.original, input);
+        }
+        let result = translate_command("dir 2>Nul", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls 2>/dev/null");
+        let result = translate_full("dir >NUL 2>&1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls >/dev/null 2>&1");
+
+        // Only redirect targets are devices
+        let result = translate_command("echo NUL", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "echo NUL");
+    }
 
-    // Split the command by operators while preserving the operators
-    let parts = split_compound_command(trimmed);
-    
-    // If there's only one part, use regular translation
-    if parts.len() == 1 {
-        return translate_command(trimmed, from_os, to_os);
+    #[test]
+    fn test_dev_null_redirect_to_windows() {
+        let result = translate_full("ls > /dev/null", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir > nul");
+        let result = translate_command("ls 2>>/dev/null", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir 2>>nul");
     }
 
-    let mut result = TranslationResult::new(
-        String::new(),
-        trimmed.to_string(),
-        from_os,
-        to_os,
-    );
+    #[test]
+    fn test_are_compatible() {
+        assert!(are_compatible(Os::Linux, Os::MacOS));
+        assert!(are_compatible(Os::FreeBSD, Os::Linux));
+        assert!(are_compatible(Os::Windows, Os::Windows));
+        assert!(!are_compatible(Os::Windows, Os::Linux));
+        assert!(!are_compatible(Os::MacOS, Os::Windows));
+    }
 
-    let mut translated_parts = Vec::new();
-    
-    for part in &parts {
-        let trimmed_part = part.trim();
-        
-        // Check if this part is an operator
-        if COMPOUND_OPERATORS.contains(&trimmed_part) {
-            translated_parts.push(trimmed_part.to_string());
-        } else if !trimmed_part.is_empty() {
-            // Translate the command
-            match translate_command(trimmed_part, from_os, to_os) {
-                Ok(cmd_result) => {
-                    translated_parts.push(cmd_result.command);
-                    // Collect warnings
-                    result.warnings.extend(cmd_result.warnings);
-                    result.had_unmapped_flags |= cmd_result.had_unmapped_flags;
-                }
-                Err(TranslationError::CommandNotFound(_)) => {
-                    // Keep original command if not found (might be a custom/unknown command)
-                    translated_parts.push(trimmed_part.to_string());
-                    result.warnings.push(format!("Command '{}' was not translated", trimmed_part.split_whitespace().next().unwrap_or(trimmed_part)));
-                }
-                Err(e) => return Err(e),
-            }
-        }
+    #[test]
+    fn test_are_compatible_predicts_passthrough() {
+        let result = translate_command("htop -d 10", Os::Linux, Os::MacOS).unwrap();
+        assert!(are_compatible(Os::Linux, Os::MacOS));
+        assert_eq!(result.command, "htop -d 10");
+        assert!(result.warnings.iter().any(|w| w.contains("passed through")));
     }
 
-    result.command = translated_parts.join(" ");
-    Ok(result)
-}
+    #[test]
+    fn test_grep_line_buffered_dropped_with_warning() {
+        let result = translate_compound_command("tail -f app.log | grep --line-buffered ERROR", Os::Linux, Os::Windows).unwrap();
+        assert!(result.command.ends_with("| findstr ERROR"));
+        assert!(!result.command.contains("--line-buffered"));
+        assert!(result.warnings.iter().any(|w| w.contains("--line-buffered")));
+    }
 
-/// Split a compound command by operators while preserving the operators
-fn split_compound_command(input: &str) -> Vec<String> {
-    let mut parts = Vec::new();
-    let mut current = String::new();
-    let chars: Vec<char> = input.chars().collect();
-    let mut i = 0;
+    #[test]
+    fn test_stdbuf_prefix_dropped_with_warning() {
+        let result = translate_command("stdbuf -oL grep -i x log.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /i x log.txt");
+        assert!(result.warnings.iter().any(|w| w.contains("stdbuf")));
 
-    while i < chars.len() {
-        // Check for two-character operators first
-        if i + 1 < chars.len() {
-            let two_char = format!("{}{}", chars[i], chars[i + 1]);
-            if two_char == "&&" || two_char == "||" {
-                if !current.is_empty() {
-                    parts.push(current);
-                    current = String::new();
-                }
-                parts.push(two_char);
-                i += 2;
-                continue;
-            }
-        }
-        
-        // Check for single-character operators
-        if chars[i] == '|' || chars[i] == ';' {
-            if !current.is_empty() {
-                parts.push(current);
-                current = String::new();
-            }
-            parts.push(chars[i].to_string());
-            i += 1;
-            continue;
-        }
+        let result = translate_command("stdbuf -o L ls -la", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir /a");
+    }
 
-        current.push(chars[i]);
-        i += 1;
+    #[test]
+    fn test_stdbuf_without_command_kept() {
+        let result = translate_command("stdbuf -oL", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "stdbuf -oL");
+        assert!(result.warnings.iter().any(|w| w.contains("without a command")));
+        assert_eq!(result.confidence, Confidence::Passthrough);
     }
 
-    if !current.is_empty() {
-        parts.push(current);
+    #[test]
+    fn test_lossy_mappings_surface_notes() {
+        let result = translate_command("du -sh .", Os::Linux, Os::Windows).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("dir /s")));
+
+        let result = translate_command("systeminfo", Os::Windows, Os::Linux).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("hardware")));
+
+        let result = translate_full("touch /tmp/a.txt", Os::Linux, Os::Windows).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("truncates")));
+
+        let result = translate_command("head -n 5 file", Os::Linux, Os::Windows).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("first lines")));
+
+        let result = translate_command("attrib +r file.txt", Os::Windows, Os::Linux).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("hidden and system attributes")));
+
+        let result = translate_command("chkdsk C: /f", Os::Windows, Os::Linux).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("unmounted filesystem")));
+
+        let result = translate_command("wget https://example.com/a.zip", Os::Linux, Os::Windows).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("redirects")));
+
+        let result = translate_command("df -h", Os::Linux, Os::Windows).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("human-readable")));
     }
 
-    parts
-}
+    #[test]
+    fn test_native_mapping_notes_surface() {
+        // stat exists on both, so only its flags are mapped
+        let result = translate_command("stat -c %s file", Os::Linux, Os::MacOS).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("Format specifiers differ")));
 
-/// Translate a script file extension between operating systems
-///
-/// # Arguments
-///
-/// * `filename` - The filename with extension to translate
-/// * `from_os` - The source operating system
-/// * `to_os` - The target operating system
-///
-/// # Returns
-///
-/// The filename with translated extension
-///
-/// # Example
-///
-/// ```
-/// use cmdx::{translate_script_extension, Os};
-///
-/// let result = translate_script_extension("script.bat", Os::Windows, Os::Linux);
-/// assert_eq!(result, "script.sh");
-///
-/// let result = translate_script_extension("script.sh", Os::Linux, Os::Windows);
-/// assert_eq!(result, "script.bat");
-/// ```
-pub fn translate_script_extension(filename: &str, from_os: Os, to_os: Os) -> String {
-    if from_os == to_os {
-        return filename.to_string();
+        let result = translate_full("stat -c %s /tmp/file", Os::Linux, Os::MacOS).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("Format specifiers differ")));
     }
-    
-    let filename = filename.trim();
-    
-    // Windows to Unix
-    if from_os == Os::Windows && to_os.is_unix_like() {
-        let filename_lower = filename.to_lowercase();
-        if let Some(base) = filename_lower.strip_suffix(".bat") {
-            return format!("{}.sh", &filename[..base.len()]);
-        }
-        if let Some(base) = filename_lower.strip_suffix(".cmd") {
-            return format!("{}.sh", &filename[..base.len()]);
-        }
-        if let Some(base) = filename_lower.strip_suffix(".ps1") {
-            return format!("{}.sh", &filename[..base.len()]);
-        }
-        if let Some(base) = filename_lower.strip_suffix(".exe") {
-            return filename[..base.len()].to_string();
-        }
+
+    #[test]
+    fn test_compound_duplicate_separators_collapsed() {
+        let result = translate_compound_command("dir & & cls", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls ; clear");
+        assert!(result.warnings.iter().any(|w| w.contains("repeated operators")));
+
+        let result = translate_compound_command("ls && && clear", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir && cls");
     }
-    
-    // Unix to Windows
-    if from_os.is_unix_like() && to_os == Os::Windows {
-        if let Some(base) = filename.strip_suffix(".sh") {
-            return format!("{}.bat", base);
-        }
-        // Files without extension might be executables - check using Path for robustness
-        if let Some(file_name) = std::path::Path::new(filename).file_name() {
-            let name = file_name.to_string_lossy();
-            if !name.contains('.') {
-                return format!("{}.exe", filename);
-            }
-        }
+
+    #[test]
+    fn test_compound_leading_and_trailing_separators_dropped() {
+        let result = translate_compound_command("& dir &", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls");
+        assert_eq!(result.original, "& dir &");
+        assert!(!result.warnings.is_empty());
+
+        let result = translate_compound_command("; ls ; clear ;", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir & cls");
+
+        assert!(matches!(
+            translate_compound_command(";;", Os::Linux, Os::Windows),
+            Err(TranslationError::EmptyCommand)
+        ));
     }
-    
-    filename.to_string()
-}
 
-/// Translate a shebang line from a script
-///
-/// # Arguments
-///
-/// * `line` - The shebang line (e.g., "#!/bin/bash")
-/// * `from_os` - The source operating system  
-/// * `to_os` - The target operating system
-///
-/// # Returns
-///
-/// The translated shebang or equivalent for target OS
-pub fn translate_shebang(line: &str, from_os: Os, to_os: Os) -> String {
-    if from_os == to_os {
-        return line.to_string();
+    #[test]
+    fn test_split_compound_non_ascii_arguments() {
+        let parts = split_compound_command("cat 'données.txt' | grep 日本語 && echo \"naïve & café\"");
+        assert_eq!(
+            parts,
+            vec!["cat 'données.txt' ", "|", " grep 日本語 ", "&&", " echo \"naïve & café\""]
+        );
+
+        let result = translate_compound_command("cat été.txt | grep ü", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "type été.txt | findstr ü");
     }
-    
-    let line = line.trim();
-    
-    // Unix to Windows - remove shebang, add @echo off for batch
-    if from_os.is_unix_like() && to_os == Os::Windows && line.starts_with("#!") {
-        return "@echo off".to_string();
+
+    #[test]
+    fn test_powershell_command_wrapper_to_pwsh() {
+        let result = translate_command("powershell -Command \"Get-ChildItem\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pwsh -Command \"ls\"");
+
+        let result = translate_command("pwsh -NoProfile -c 'Write-Output hi'", Os::Windows, Os::MacOS).unwrap();
+        assert_eq!(result.command, "pwsh -NoProfile -c 'echo hi'");
+
+        let result = translate_command("powershell -File build.ps1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pwsh -File build.ps1");
     }
-    
-    // Windows to Unix - convert @echo off to shebang
-    if from_os == Os::Windows && to_os.is_unix_like() && line.to_lowercase().starts_with("@echo off") {
-        return "#!/bin/bash".to_string();
+
+    #[test]
+    fn test_powershell_wrapper_keeps_untranslated_cmdlets() {
+        let result = translate_command("powershell -Command \"Get-Content a.txt | Sort-Object\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pwsh -Command \"cat a.txt | Sort-Object\"");
+        assert!(result.warnings.iter().any(|w| w.contains("Sort-Object")));
     }
-    
-    line.to_string()
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_rm_flags_emitted_before_operands() {
+        for input in ["rm -rf /tmp/foo", "rm -fr /tmp/foo", "rm /tmp/foo -rf"] {
+            let result = translate_command(input, Os::Linux, Os::Windows).unwrap();
+            assert_eq!(result.command, "del /s /q /f /tmp/foo", "{}", input);
+        }
+        let result = translate_full("rm -rf /tmp/foo", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /s /q /f C:\\tmp\\foo");
+    }
 
     #[test]
-    fn test_parse_command() {
-        let (cmd, args) = parse_command("ls -la /home");
-        assert_eq!(cmd, "ls");
-        assert_eq!(args, vec!["-la", "/home"]);
+    fn test_mixed_flags_and_operands_keep_operand_order() {
+        let result = translate_command("rm file1 -r file2", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /s file1 file2");
+
+        // Dash-style values stay next to their flag
+        let result = translate_command("ping host -c 5", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "ping host -n 5");
     }
 
     #[test]
-    fn test_parse_command_no_args() {
-        let (cmd, args) = parse_command("ls");
-        assert_eq!(cmd, "ls");
-        assert!(args.is_empty());
+    fn test_write_script_uses_target_line_endings() {
+        let path = std::env::temp_dir().join(format!("cmdx-write-script-{}.bat", std::process::id()));
+        write_script(&path, "@echo off\necho hi\n", Os::Windows).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "@echo off\r\necho hi\r\n");
+
+        write_script(&path, "#!/bin/sh\r\necho hi", Os::Linux).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "#!/bin/sh\necho hi");
+        std::fs::remove_file(&path).unwrap();
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_parse_command_empty() {
-        let (cmd, args) = parse_command("");
-        assert!(cmd.is_empty());
-        assert!(args.is_empty());
+    fn test_write_script_makes_shell_scripts_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("cmdx-write-script-{}.sh", std::process::id()));
+        write_script(&path, "#!/bin/sh\nls\n", Os::Linux).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o111, 0o111);
     }
 
     #[test]
-    fn test_translate_dir_to_ls() {
-        let result = translate_command("dir", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "ls");
+    fn test_quoted_empty_argument_preserved() {
+        let result = translate_command("echo \"\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo \"\"");
+        let result = translate_command("echo \"\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "echo \"\"");
+        let result = translate_full("mkdir \"\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "mkdir \"\"");
+        let result = translate_full("copy \"\" C:\\x", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cp \"\" /mnt/c/x");
     }
 
     #[test]
-    fn test_translate_dir_with_flags() {
-        let result = translate_command("dir /w", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ls"));
-        assert!(result.command.contains("-C"));
+    fn test_single_quoted_empty_argument_to_windows() {
+        let result = translate_command("echo ''", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "echo \"\"");
+        let result = translate_command("grep '' file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr \"\" file");
     }
 
     #[test]
-    fn test_translate_ls_to_dir() {
-        let result = translate_command("ls", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "dir");
+    fn test_compound_redirect_target_paths_translated() {
+        let result = translate_compound_command("dir /w > C:\\log.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -C > /mnt/c/log.txt");
+
+        let result = translate_compound_command("cat file > /tmp/x", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "type file > C:\\tmp\\x");
+
+        let result = translate_compound_command("dir /s 2> C:\\err.txt && cls", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -R 2> /mnt/c/err.txt && clear");
     }
 
     #[test]
-    fn test_translate_ls_with_flags() {
-        let result = translate_command("ls -la", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("dir"));
+    fn test_compound_redirect_edge_cases() {
+        let result = translate_compound_command("ls -la >> /tmp/log 2>&1", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir /a >> C:\\tmp\\log 2>&1");
+
+        // No spaces around the operator
+        let result = translate_compound_command("dir>out.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls >out.txt");
+
+        let result = translate_compound_command("sort < C:\\in.txt | more", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "sort < /mnt/c/in.txt | less");
     }
 
     #[test]
-    fn test_translate_copy_to_cp() {
-        let result = translate_command("copy /y", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("cp"));
-        assert!(result.command.contains("-f"));
+    fn test_split_redirections() {
+        let (command, redirections) = split_redirections("echo \"a > b\" 2>&1 >out.txt");
+        assert_eq!(command, "echo \"a > b\"");
+        let parts: Vec<_> = redirections.iter().map(|r| (r.operator.as_str(), r.target.as_str(), r.spaced)).collect();
+        assert_eq!(parts, vec![("2>&", "1", false), (">", "out.txt", false)]);
     }
 
     #[test]
-    fn test_translate_cls_to_clear() {
-        let result = translate_command("cls", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "clear");
+    fn test_grep_glued_count_values() {
+        let result = translate_command("grep -m5 pat file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr pat file");
+        assert!(result.warnings.iter().any(|w| w.contains("-m 5")));
+
+        let result = translate_command("grep -A3 pat file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr pat file");
+        assert!(result.warnings.iter().any(|w| w.contains("-A 3")));
+
+        let result = translate_command("grep -iB2 pat file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /i pat file");
     }
 
     #[test]
-    fn test_translate_clear_to_cls() {
-        let result = translate_command("clear", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "cls");
+    fn test_grep_separate_count_value_not_taken_as_pattern() {
+        let result = translate_command("grep -m 5 pat file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr pat file");
+        assert_eq!(grep_value_flag("-m5"), Some(("", 'm', "5")));
+        assert_eq!(grep_value_flag("-in"), None);
+        assert_eq!(grep_value_flag("--max-count"), None);
     }
 
     #[test]
-    fn test_translate_grep_to_findstr() {
-        let result = translate_command("grep -i pattern", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("findstr"));
-        assert!(result.command.contains("/i"));
+    fn test_grep_trailing_count_flag_without_value() {
+        let result = translate_command("grep pat file -m", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr pat file");
+        assert!(result.warnings.iter().any(|w| w == "grep -m is missing its value and was dropped"));
     }
 
     #[test]
-    fn test_translate_findstr_to_grep() {
-        let result = translate_command("findstr /i pattern", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("grep"));
-        assert!(result.command.contains("-i"));
+    fn test_cp_archive_to_robocopy() {
+        let result = translate_command("cp -a src dst", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "robocopy src dst /e /copyall /dcopy:t");
+        assert!(result.warnings.iter().any(|w| w.contains("ACLs")));
+
+        let result = translate_command("cp --archive src dst", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "robocopy src dst /e /copyall /dcopy:t");
+
+        let result = translate_command("cp -av src dst", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "robocopy src dst /e /copyall /dcopy:t");
     }
 
     #[test]
-    fn test_translate_same_os() {
-        let result = translate_command("ls -la", Os::Linux, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "ls -la");
+    fn test_flag_target_replaces_command() {
+        let result = translate_command("cp -r src dst", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "xcopy /s /e /y src dst");
+
+        let result = translate_command("rm -d empty", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "rmdir empty");
     }
 
     #[test]
-    fn test_translate_empty_command() {
-        let result = translate_command("", Os::Windows, Os::Linux);
-        assert!(result.is_err());
-        match result {
-            Err(TranslationError::EmptyCommand) => {}
-            _ => panic!("Expected EmptyCommand error"),
+    fn test_powershell_cmdlets_with_recurse_and_force() {
+        let cases = [
+            ("Get-ChildItem -Recurse", "ls -R"),
+            ("get-childitem -recurse -force", "ls -R -a"),
+            ("Remove-Item -Recurse -Force build", "rm -r -f build"),
+            ("Copy-Item -Recurse -Force src dst", "cp -r -f src dst"),
+            ("Move-Item -Force old new", "mv -f old new"),
+            ("Get-Content -Path notes.txt", "cat notes.txt"),
+            ("Select-String -Pattern error -Path log.txt", "grep -i error log.txt"),
+        ];
+        for (input, expected) in cases {
+            let result = translate_command(input, Os::Windows, Os::Linux).unwrap();
+            assert_eq!(result.command, expected, "input: {}", input);
         }
     }
 
     #[test]
-    fn test_translate_command_not_found() {
-        let result = translate_command("nonexistent", Os::Windows, Os::Linux);
-        assert!(result.is_err());
-        match result {
-            Err(TranslationError::CommandNotFound(_)) => {}
-            _ => panic!("Expected CommandNotFound error"),
-        }
+    fn test_mixed_case_operands_are_preserved() {
+        let result = translate_command("grep -i MyPattern Notes.TXT", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /i MyPattern Notes.TXT");
+
+        let result = translate_command("findstr /C:\"Some String\" Notes.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "grep -F -e \"Some String\" Notes.txt");
+
+        let result = translate_command("DIR /W MyDir", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ls -C MyDir");
+    }
+
+    #[test]
+    fn test_unix_flags_match_case_sensitively() {
+        let result = translate_command("ls -r", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir /o:-n");
+
+        let result = translate_command("ls -R", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir /s");
     }
 
     #[test]
-    fn test_translate_command_str() {
-        let result = translate_command_str("dir", "windows", "linux");
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().command, "ls");
+    fn test_one_line_loops_are_kept_whole() {
+        let result = translate_compound_command("for f in *.txt; do echo $f; done", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "for f in *.txt; do echo $f; done");
+        assert!(result.had_unsupported_constructs);
+        assert!(result.warnings.iter().any(|w| w.contains("Shell loop ('for')")));
+
+        let result = translate_compound_command("while true; do ls -la; done", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "while true; do dir /a; done");
+
+        let result = translate_compound_command("until false; do clear; done", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "until false; do cls; done");
     }
 
     #[test]
-    fn test_translate_command_str_invalid_os() {
-        let result = translate_command_str("dir", "invalid", "linux");
-        assert!(result.is_err());
-        match result {
-            Err(TranslationError::InvalidOs(_)) => {}
-            _ => panic!("Expected InvalidOs error"),
-        }
+    fn test_loop_inside_compound_command() {
+        let result = translate_compound_command(
+            "ls && for f in a; do for g in b; do cat $g; done; done; clear",
+            Os::Linux,
+            Os::Windows,
+        )
+        .unwrap();
+        assert_eq!(result.command, "dir && for f in a; do for g in b; do type $g; done; done & cls");
+
+        // Without a closing `done` the segments are translated one by one
+        let result = translate_compound_command("for f in a; do ls", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "for f in a & do ls");
     }
 
     #[test]
-    fn test_translate_batch() {
-        let commands = vec!["dir", "cls", "copy"];
-        let results = translate_batch(&commands, Os::Windows, Os::Linux);
-        assert_eq!(results.len(), 3);
-        assert!(results.iter().all(|r| r.is_ok()));
+    fn test_set_assignment_to_export() {
+        let result = translate_command("set FOO=bar", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export FOO=bar");
+
+        let result = translate_command("set \"GREETING=hello %USERNAME%\"", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export GREETING=\"hello $USER\"");
+
+        let result = translate_command("set PATH=%PATH%;C:\\bin", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export PATH=\"$PATH:/mnt/c/bin\"");
+
+        // Listing variables is unchanged
+        let result = translate_command("set", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "env");
     }
 
     #[test]
-    fn test_unix_to_unix_passthrough() {
-        let result = translate_command("some_unix_cmd", Os::Linux, Os::MacOS);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "some_unix_cmd");
+    fn test_export_assignment_to_set() {
+        let result = translate_command("export FOO=bar", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set FOO=bar");
+
+        let result = translate_command("export CACHE=\"$HOME/cache\"", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set CACHE=%USERPROFILE%/cache");
+
+        let result = translate_command("export PATH=$PATH:/usr/local/bin", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "set PATH=%PATH%;C:\\usr\\local\\bin");
     }
 
     #[test]
-    fn test_translate_tasklist_to_ps() {
-        let result = translate_command("tasklist", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ps"));
+    fn test_set_arithmetic_and_prompt() {
+        let result = translate_command("set /a COUNT=%COUNT%+1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "export COUNT=$(($COUNT+1))");
+        assert!(result.warnings.iter().any(|w| w.contains("set /a")));
+
+        let result = translate_command("set /p NAME=Name? ", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "read -r -p \"Name?\" NAME");
+        assert!(result.warnings.iter().any(|w| w.contains("set /p")));
     }
 
     #[test]
-    fn test_translate_ps_to_tasklist() {
-        let result = translate_command("ps", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("tasklist"));
+    fn test_multi_file_delete_keeps_all_files() {
+        let result = translate_command("rm -f a.txt b.txt c.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /q /f a.txt b.txt c.txt");
+
+        let result = translate_command("rm a.txt -f b.txt -f c.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /q /f a.txt b.txt c.txt");
+
+        let result = translate_command("del /q a.txt b.txt c.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "rm -f a.txt b.txt c.txt");
+
+        let result = translate_command("del /f /q a.txt /q b.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "rm -f a.txt b.txt");
     }
 
     #[test]
-    fn test_translate_ping_flags() {
-        let result = translate_command("ping -n 5 localhost", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ping"));
-        assert!(result.command.contains("-c"));
+    fn test_gnu_to_bsd_flags() {
+        let result = translate_command("ls --color=auto", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "ls -G");
+
+        let result = translate_command("stat -c %s file", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "stat -f %s file");
+
+        let result = translate_command("stat -f %z file", Os::MacOS, Os::Linux).unwrap();
+        assert_eq!(result.command, "stat -c %z file");
     }
 
     #[test]
-    fn test_compound_command_and() {
-        let result = translate_compound_command("dir && cls", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ls"));
-        assert!(result.command.contains("&&"));
-        assert!(result.command.contains("clear"));
+    fn test_sed_in_place_between_gnu_and_bsd() {
+        let result = translate_command("sed -i s/a/b/ file", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "sed -i '' s/a/b/ file");
+        assert!(result.warnings.iter().any(|w| w.contains("sed -i")));
+
+        let result = translate_command("sed -i.bak -r s/a+/b/ file", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "sed -i .bak -E s/a+/b/ file");
+
+        let result = translate_command("sed -i '' s/a/b/ file", Os::MacOS, Os::Linux).unwrap();
+        assert_eq!(result.command, "sed -i s/a/b/ file");
+
+        let result = translate_command("sed -i .bak s/a/b/ file", Os::MacOS, Os::Linux).unwrap();
+        assert_eq!(result.command, "sed -i.bak s/a/b/ file");
     }
 
     #[test]
-    fn test_compound_command_or() {
-        let result = translate_compound_command("dir || cls", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ls"));
-        assert!(result.command.contains("||"));
-        assert!(result.command.contains("clear"));
+    fn test_source_to_call() {
+        let result = translate_command("source ./env.sh", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "call env.bat");
+
+        let result = translate_command(". ./env.sh", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "call env.bat");
+
+        let result = translate_full("source /opt/app/env.sh prod", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "call C:\\opt\\app\\env.bat prod");
     }
 
     #[test]
-    fn test_compound_command_pipe() {
-        let result = translate_compound_command("dir | findstr test", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ls"));
-        assert!(result.command.contains("|"));
-        assert!(result.command.contains("grep"));
+    fn test_call_to_source() {
+        let result = translate_command("call env.bat", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "source env.sh");
+
+        let result = translate_full("call C:\\scripts\\setup.cmd", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "source /mnt/c/scripts/setup.sh");
+
+        // Subroutine calls have no shell equivalent
+        assert!(translate_command("call :cleanup", Os::Windows, Os::Linux).is_err());
     }
 
     #[test]
-    fn test_compound_command_semicolon() {
-        let result = translate_compound_command("ls; clear", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("dir"));
-        assert!(result.command.contains(";"));
-        assert!(result.command.contains("cls"));
+    fn test_grep_whole_word_to_findstr_regex() {
+        let result = translate_command("grep -w foo file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /r \"\\<foo\\>\" file");
+        assert!(result.warnings.iter().any(|w| w.contains("grep -w")));
+
+        let result = translate_command("grep -iw foo file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /r /i \"\\<foo\\>\" file");
+
+        let result = translate_command("grep -w -e foo -e bar file", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "findstr /r \"\\<foo\\> \\<bar\\>\" file");
     }
 
     #[test]
-    fn test_compound_command_single() {
-        let result = translate_compound_command("dir", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().command, "ls");
+    fn test_translate_full_converts_env_vars_in_paths() {
+        let result = translate_full("copy %USERPROFILE%\\file.txt D:\\dest", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cp $HOME/file.txt /mnt/d/dest");
+
+        let result = translate_full("cp $HOME/file.txt /tmp/backup", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "copy %USERPROFILE%\\file.txt C:\\tmp\\backup");
+        assert!(result.warnings.iter().any(|w| w.contains("C: drive")));
+
+        let result = translate_full("type %TEMP%\\log.txt", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "cat $TMPDIR/log.txt");
     }
 
     #[test]
-    fn test_split_compound_command() {
-        let parts = split_compound_command("dir && cls || type");
-        assert_eq!(parts.len(), 5);
-        assert_eq!(parts[0].trim(), "dir");
-        assert_eq!(parts[1], "&&");
-        assert_eq!(parts[2].trim(), "cls");
-        assert_eq!(parts[3], "||");
-        assert_eq!(parts[4].trim(), "type");
+    fn test_translate_full_does_not_retranslate_home() {
+        let result = translate_full("cat ~/notes.txt", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "type %USERPROFILE%\\notes.txt");
     }
 
     #[test]
-    fn test_native_command_passthrough() {
-        // If we're translating from Linux to Windows, but the command is already
-        // a Windows command (like 'dir'), it should pass through unchanged
-        let result = translate_command("dir", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "dir");
-        assert!(result.warnings.iter().any(|w| w.contains("already")));
+    fn test_minimal_coreutils_drops_gnu_flags() {
+        let options = TranslationOptions { minimal_coreutils: true, ..Default::default() };
+
+        let result = translate_command_with_options("ls --color=auto -la", Os::Linux, Os::Linux, &options).unwrap();
+        assert_eq!(result.command, "ls -la");
+        assert!(result.warnings.iter().any(|w| w.contains("--color=auto") && w.contains("BusyBox")));
+
+        let result = translate_command_with_options("ls -G", Os::MacOS, Os::Linux, &options).unwrap();
+        assert_eq!(result.command, "ls");
+
+        // Off by default
+        let result = translate_command_with_options("ls --color=auto", Os::Linux, Os::Linux, &TranslationOptions::default()).unwrap();
+        assert_eq!(result.command, "ls --color=auto");
     }
 
     #[test]
-    fn test_native_command_passthrough_with_flags() {
-        // Windows command with Windows flags should pass through
-        let result = translate_command("dir /w", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "dir /w");
+    fn test_taskkill_image_to_pkill() {
+        let result = translate_command("taskkill /IM notepad.exe /F", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pkill -9 notepad");
+
+        let result = translate_command("taskkill /im Code.EXE", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pkill Code");
     }
 
     #[test]
-    fn test_native_unix_command_passthrough_to_linux() {
-        // If we're translating from Windows to Linux, but the command is already
-        // a Linux command (like 'ls'), it should pass through unchanged
-        let result = translate_command("ls", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "ls");
-        assert!(result.warnings.iter().any(|w| w.contains("already")));
+    fn test_taskkill_pid_to_kill() {
+        let result = translate_command("taskkill /PID 1234 /F", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "kill -9 1234");
+
+        let result = translate_command("taskkill /pid 1 /pid 2 /t", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "kill 1 2");
+        assert!(result.warnings.iter().any(|w| w.contains("/t")));
     }
 
     #[test]
-    fn test_native_unix_command_passthrough_with_flags() {
-        // Unix command with Unix flags should pass through
-        let result = translate_command("ls -la", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert_eq!(result.command, "ls -la");
+    fn test_taskkill_non_ascii_argument() {
+        // Lowercasing 'İ' changes its byte length
+        let result = translate_command("taskkill İ:", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "kill İ:");
+
+        let result = translate_command("taskkill /IM:İmage.exe", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pkill İmage");
     }
 
     #[test]
-    fn test_common_command_with_different_flags() {
-        // ping exists on both OSes but has different flag syntax
-        // When translating from Windows to Linux, flags should be translated
-        let result = translate_command("ping -n 5 localhost", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.command.contains("ping"));
-        assert!(result.command.contains("-c")); // -n becomes -c
+    fn test_pushd_translates_path() {
+        let result = translate_command("pushd C:\\temp", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pushd /mnt/c/temp");
+        assert!(result.warnings.is_empty());
+
+        let result = translate_command("popd", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "popd");
     }
 
     #[test]
-    fn test_translate_script_extension_bat_to_sh() {
-        let result = translate_script_extension("script.bat", Os::Windows, Os::Linux);
-        assert_eq!(result, "script.sh");
+    fn test_pushd_unc_path_warns() {
+        let result = translate_command("pushd \\\\server\\share", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "pushd //server/share");
+        assert!(result.warnings.iter().any(|w| w.contains("UNC path maps a temporary drive")));
     }
 
     #[test]
-    fn test_translate_script_extension_cmd_to_sh() {
-        let result = translate_script_extension("build.cmd", Os::Windows, Os::Linux);
-        assert_eq!(result, "build.sh");
+    fn test_batch_for_loop_to_bash() {
+        let result = translate_compound_command("for %%i in (*.txt) do type %%i", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "for i in *.txt; do cat $i; done");
+        assert!(result.warnings.is_empty());
+
+        let result = translate_command("for %i in (a.txt, b.txt) do echo %i", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "for i in a.txt b.txt; do echo $i; done");
+
+        let result = translate_compound_command("for /l %%n in (1,1,5) do echo %%n", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "for n in $(seq 1 1 5); do echo $n; done");
+
+        // Recursive and directory loops, and multi-line blocks, stay unsupported
+        for input in ["for /d %%d in (*) do echo %%d", "for %%i in (*.txt) do ("] {
+            assert!(matches!(
+                translate_compound_command(input, Os::Windows, Os::Linux),
+                Err(TranslationError::UnsupportedConstruct(_))
+            ));
+        }
     }
 
     #[test]
-    fn test_translate_script_extension_ps1_to_sh() {
-        let result = translate_script_extension("deploy.ps1", Os::Windows, Os::Linux);
-        assert_eq!(result, "deploy.sh");
+    fn test_batch_for_f_to_while_read() {
+        let result = translate_compound_command(
+            "for /f \"tokens=*\" %%i in (file.txt) do echo %%i",
+            Os::Windows,
+            Os::Linux,
+        )
+        .unwrap();
+        assert_eq!(result.command, "while IFS= read -r i; do echo $i; done < file.txt");
+        assert!(result.warnings.iter().any(|w| w.contains("approximately")));
+
+        let result = translate_compound_command(
+            "for /f \"usebackq delims=\" %%l in (`dir /b`) do echo %%~l && cls",
+            Os::Windows,
+            Os::Linux,
+        )
+        .unwrap();
+        assert_eq!(result.command, "ls -1 | while IFS= read -r l; do echo $l && clear; done");
+
+        let result = translate_compound_command(
+            "for /f \"tokens=1,2 delims=,\" %%a in (data.csv) do echo %%a",
+            Os::Windows,
+            Os::Linux,
+        )
+        .unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("'tokens=1,2' is not supported")));
     }
 
     #[test]
-    fn test_translate_script_extension_sh_to_bat() {
-        let result = translate_script_extension("script.sh", Os::Linux, Os::Windows);
-        assert_eq!(result, "script.bat");
+    fn test_route_to_ip_route() {
+        let result = translate_command("route print", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ip route show");
+
+        let result = translate_command("route add 10.0.0.0 mask 255.0.0.0 192.168.1.1", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ip route add 10.0.0.0/8 via 192.168.1.1");
+        assert!(result.warnings.iter().any(|w| w.contains("CIDR")));
+
+        let result = translate_command("route -p add 0.0.0.0 mask 0.0.0.0 192.168.1.1 metric 10", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ip route add default via 192.168.1.1 metric 10");
+        assert!(result.warnings.iter().any(|w| w.contains("route -p")));
+
+        let result = translate_command("route delete 10.0.0.0", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ip route del 10.0.0.0");
     }
 
     #[test]
-    fn test_translate_script_extension_exe_removal() {
-        let result = translate_script_extension("program.exe", Os::Windows, Os::Linux);
-        assert_eq!(result, "program");
+    fn test_netsh_set_address_to_ip_addr() {
+        let result = translate_command(
+            "netsh interface ip set address name=\"Ethernet\" static 192.168.1.10 255.255.255.0 192.168.1.1",
+            Os::Windows,
+            Os::Linux,
+        )
+        .unwrap();
+        assert_eq!(result.command, "ip addr replace 192.168.1.10/24 dev Ethernet && ip route replace default via 192.168.1.1");
+        assert!(result.warnings.iter().any(|w| w.contains("not 1:1")));
+
+        let result = translate_command("netsh interface ip set address eth0 dhcp", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "dhclient eth0");
+
+        let result = translate_command("netsh wlan show profiles", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ip addr");
+        assert!(result.had_unmapped_flags);
     }
 
     #[test]
-    fn test_translate_script_extension_add_exe() {
-        let result = translate_script_extension("program", Os::Linux, Os::Windows);
-        assert_eq!(result, "program.exe");
+    fn test_bare_netsh_to_ip_addr() {
+        let result = translate_command("netsh", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "ip addr");
+        assert!(result.warnings.iter().any(|w| w.starts_with("netsh without a context")));
+        assert!(!result.warnings.iter().any(|w| w.contains("''")));
     }
 
     #[test]
-    fn test_translate_script_extension_same_os() {
-        let result = translate_script_extension("script.bat", Os::Windows, Os::Windows);
-        assert_eq!(result, "script.bat");
+    fn test_normalize_command() {
+        assert_eq!(normalize_command("ls  -la   /tmp", Os::Linux), "ls -a -l /tmp");
+        assert_eq!(normalize_command("ls -l -a -l /tmp", Os::Linux), "ls -a -l /tmp");
+        assert_eq!(normalize_command("DIR /S /w C:\\", Os::Windows), "dir /s /w C:\\");
+        assert_eq!(normalize_command("rm -- -rf", Os::Linux), "rm -- -rf");
+        assert_eq!(normalize_command("   ", Os::Linux), "");
     }
 
     #[test]
-    fn test_translate_shebang_unix_to_windows() {
-        let result = translate_shebang("#!/bin/bash", Os::Linux, Os::Windows);
-        assert_eq!(result, "@echo off");
+    fn test_round_trip_stable_commands() {
+        let round_trip = |input: &str, from_os: Os, to_os: Os| {
+            let there = translate_command(input, from_os, to_os).unwrap().command;
+            let back = translate_command(&there, to_os, from_os).unwrap().command;
+            assert_eq!(normalize_command(&back, from_os), normalize_command(input, from_os), "{}", input);
+        };
+
+        for input in ["dir", "dir /w /s", "dir /a", "copy /y a.txt b.txt", "move a b", "findstr /i x f"] {
+            round_trip(input, Os::Windows, Os::Linux);
+        }
+        for input in ["ls -la", "ls -l -a", "ls -R", "cp -f a.txt b.txt", "rm -rf tmp", "rm -f x"] {
+            round_trip(input, Os::Linux, Os::Windows);
+        }
     }
 
     #[test]
-    fn test_translate_shebang_windows_to_unix() {
-        let result = translate_shebang("@echo off", Os::Windows, Os::Linux);
-        assert_eq!(result, "#!/bin/bash");
+    fn test_bundled_short_flags_split() {
+        for input in ["ls -laR", "ls -Ral", "ls -l -a -R"] {
+            let result = translate_command(input, Os::Linux, Os::Windows).unwrap();
+            assert_eq!(normalize_command(&result.command, Os::Windows), "dir /a /s", "{}", input);
+        }
+
+        // The explicit combined entry still wins
+        let result = translate_command("rm -rf build", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "del /s /q /f build");
+
+        // Known letters are split out of a partly unmapped bundle
+        let result = translate_command("ls -lZ", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "dir -Z");
+        assert!(result.had_unmapped_flags);
+        assert!(result.warnings.iter().any(|w| w.contains("'-Z' (from '-lZ')")));
+
+        // Flags with attached values aren't bundles
+        let result = translate_command("ping -c4 host", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.command, "ping -n 4 host");
     }
 
     #[test]
-    fn test_translate_shebang_same_os() {
-        let result = translate_shebang("#!/bin/bash", Os::Linux, Os::Linux);
-        assert_eq!(result, "#!/bin/bash");
+    fn test_wildcard_operands_warn() {
+        let result = translate_command("del *.tmp", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "rm *.tmp");
+        assert!(result.warnings.iter().any(|w| w.contains("Wildcards in '*.tmp'")));
+
+        let result = translate_command("del *.*", Os::Windows, Os::Linux).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("'*.*' matches every file")));
+
+        let result = translate_command("rm -f a?.log", Os::Linux, Os::Windows).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("Wildcards in 'a?.log'")));
+
+        // Quoted patterns aren't expanded, and same-family OSes glob alike
+        let result = translate_command("dir \"*.txt\"", Os::Windows, Os::Linux).unwrap();
+        assert!(!result.warnings.iter().any(|w| w.contains("Wildcards")));
+        let result = translate_command("sed -n p *.txt", Os::Linux, Os::MacOS).unwrap();
+        assert!(!result.warnings.iter().any(|w| w.contains("Wildcards")));
     }
 
     #[test]
-    fn test_translate_full_windows_to_linux_with_path() {
-        let result = translate_full("copy C:\\Users\\file.txt D:\\backup\\", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let r = result.unwrap();
-        assert!(r.command.contains("cp"));
-        assert!(r.command.contains("/mnt/c/"));
-        assert!(r.command.contains("/mnt/d/"));
+    fn test_variable_references_are_not_wildcards() {
+        let result = translate_full("echo %ERRORLEVEL%", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "echo $?");
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.confidence, Confidence::Exact);
     }
 
     #[test]
-    fn test_translate_full_linux_to_windows_with_path() {
-        let result = translate_full("cp /mnt/c/Users/file.txt /tmp/backup", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let r = result.unwrap();
-        assert!(r.command.contains("copy"));
-        assert!(r.command.contains("C:"));
+    fn test_translation_result_builder() {
+        let result = TranslationResult::new("ls".to_string(), "dir /z".to_string(), Os::Windows, Os::Linux)
+            .with_warning("first")
+            .with_warnings(vec!["second".to_string(), "third".to_string()])
+            .with_warning(String::from("fourth"))
+            .mark_unmapped_flags();
+        assert_eq!(result.warnings, ["first", "second", "third", "fourth"]);
+        assert!(result.had_unmapped_flags);
+        assert!(!result.had_unsupported_constructs);
+
+        let plain = TranslationResult::new("ls".to_string(), "dir".to_string(), Os::Windows, Os::Linux);
+        assert!(plain.warnings.is_empty());
+        assert!(!plain.had_unmapped_flags);
     }
 
     #[test]
-    fn test_translate_full_dir_with_path() {
-        let result = translate_full("dir C:\\Windows", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let r = result.unwrap();
-        assert!(r.command.contains("ls"));
-        assert!(r.command.contains("/mnt/c/"));
+    fn test_batch_echo_blank_line() {
+        for input in ["echo.", "echo,", "ECHO:", "@echo."] {
+            let result = translate_command(input, Os::Windows, Os::Linux).unwrap();
+            assert_eq!(result.command, "echo \"\"", "{}", input);
+        }
+        let result = translate_command("echo.Done", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "echo Done");
+        let result = translate_compound_command("echo.&& dir", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "echo \"\" && ls");
+
+        let result = translate_command("@echo off", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, ":");
+        assert!(result.warnings.iter().any(|w| w.contains("no-op")));
+        let result = translate_command("@echo hi", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "echo hi");
     }
 
     #[test]
-    fn test_translate_full_ls_with_path() {
-        let result = translate_full("ls /home/user/documents", Os::Linux, Os::Windows);
-        assert!(result.is_ok());
-        let r = result.unwrap();
-        assert!(r.command.contains("dir"));
-        assert!(r.command.contains("Users"));
+    fn test_confidence_exact() {
+        let result = translate_command("cls", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.command, "clear");
+        assert_eq!(result.confidence, Confidence::Exact);
     }
 
     #[test]
-    fn test_translate_full_preserves_flags_and_paths() {
-        let result = translate_full("copy /y C:\\src\\file.txt D:\\dest\\", Os::Windows, Os::Linux);
-        assert!(result.is_ok());
-        let r = result.unwrap();
-        assert!(r.command.contains("cp"));
-        assert!(r.command.contains("-f")); // /y -> -f
-        assert!(r.command.contains("/mnt/c/"));
-        assert!(r.command.contains("/mnt/d/"));
+    fn test_confidence_approximate() {
+        let result = translate_command("du", Os::Linux, Os::Windows).unwrap();
+        assert!(result.command.starts_with("dir /s"));
+        assert_eq!(result.confidence, Confidence::Approximate);
+
+        // Unmapped flags make an otherwise exact mapping approximate
+        let result = translate_command("dir /z", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.confidence, Confidence::Approximate);
     }
 
     #[test]
-    fn test_is_path_argument() {
-        // Windows paths
-        assert!(is_path_argument("C:\\Users", Os::Windows));
-        assert!(is_path_argument("D:\\Documents\\file.txt", Os::Windows));
-        
-        // Unix paths
-        assert!(is_path_argument("/home/user", Os::Linux));
-        assert!(is_path_argument("~/Documents", Os::Linux));
-        assert!(is_path_argument("./local/file", Os::Linux));
-        
-        // Not paths (flags)
-        assert!(!is_path_argument("-la", Os::Linux));
-        assert!(!is_path_argument("/w", Os::Windows));
-        assert!(!is_path_argument("--help", Os::Linux));
+    fn test_confidence_passthrough() {
+        let result = translate_command("nmap -sV localhost", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.command, "nmap -sV localhost");
+        assert_eq!(result.confidence, Confidence::Passthrough);
+
+        let result = translate_full("nmap -sV localhost", Os::Linux, Os::MacOS).unwrap();
+        assert_eq!(result.confidence, Confidence::Passthrough);
+    }
+
+    #[test]
+    fn test_confidence_compound_takes_lowest() {
+        let result = translate_compound_command("cls && dir", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.confidence, Confidence::Exact);
+
+        let result = translate_compound_command("cls && mytool --run", Os::Windows, Os::Linux).unwrap();
+        assert_eq!(result.confidence, Confidence::Passthrough);
     }
 }
@@ -44,6 +44,8 @@ lazy_static! {
         m.insert("COMMONPROGRAMFILES", "/usr/local");
         m.insert("PROGRAMDATA", "/var");
         m.insert("ALLUSERSPROFILE", "/var");
+        // Exit status of the last command
+        m.insert("ERRORLEVEL", "?");
         m
     };
 
@@ -59,6 +61,7 @@ lazy_static! {
         m.insert("HOSTNAME", "COMPUTERNAME");
         m.insert("PWD", "CD");
         m.insert("SHELL", "COMSPEC");
+        m.insert("?", "ERRORLEVEL");
         m
     };
 }
@@ -168,6 +171,12 @@ fn translate_unix_to_windows_env(input: &str) -> String {
                     continue;
                 }
             }
+            // Handle $? (exit status of the last command)
+            else if chars[i + 1] == '?' {
+                result.push_str("%ERRORLEVEL%");
+                i += 2;
+                continue;
+            }
             // Handle $VAR format
             else if chars[i + 1].is_alphanumeric() || chars[i + 1] == '_' {
                 let start = i + 1;
@@ -282,4 +291,12 @@ mod tests {
         let result = translate_env_vars("$TMPDIR", Os::Linux, Os::Windows);
         assert_eq!(result, "%TEMP%");
     }
+
+    #[test]
+    fn test_exit_status_variable() {
+        assert_eq!(translate_env_vars("echo $?", Os::Linux, Os::Windows), "echo %ERRORLEVEL%");
+        assert_eq!(translate_env_vars("echo ${?}", Os::Linux, Os::Windows), "echo %ERRORLEVEL%");
+        assert_eq!(translate_env_vars("echo %ERRORLEVEL%", Os::Windows, Os::Linux), "echo $?");
+        assert_eq!(translate_env_vars("echo %errorlevel%", Os::Windows, Os::Linux), "echo $?");
+    }
 }
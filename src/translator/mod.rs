@@ -5,3 +5,5 @@ pub mod command_map;
 pub mod engine;
 pub mod path;
 pub mod env;
+pub mod package;
+pub mod stream;
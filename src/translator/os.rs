@@ -5,7 +5,10 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 
 /// Supported operating systems for command translation
+///
+/// Serialized as the lowercase canonical name accepted by [`Os::parse`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Os {
     Windows,
     Linux,
@@ -55,18 +58,53 @@ impl FromStr for Os {
         match s.to_lowercase().as_str() {
             "windows" | "win" | "win32" | "win64" => Ok(Os::Windows),
             "linux" | "gnu/linux" => Ok(Os::Linux),
-            "macos" | "darwin" | "osx" | "mac" => Ok(Os::MacOS),
-            "freebsd" => Ok(Os::FreeBSD),
+            "macos" | "darwin" | "osx" | "mac" | "apple" => Ok(Os::MacOS),
+            "freebsd" | "bsd" => Ok(Os::FreeBSD),
             "openbsd" => Ok(Os::OpenBSD),
             "netbsd" => Ok(Os::NetBSD),
             "solaris" | "sunos" => Ok(Os::Solaris),
             "android" => Ok(Os::Android),
             "ios" => Ok(Os::Ios),
-            _ => Err(ParseOsError(s.to_string())),
+            "unknown" => Ok(Os::Unknown),
+            lower => from_target_triple(lower).ok_or_else(|| ParseOsError(s.to_string())),
         }
     }
 }
 
+/// Extract the OS from a target triple like `x86_64-pc-windows-msvc`
+///
+/// Android and iOS are checked first since their triples also name
+/// `linux` and `apple`.
+fn from_target_triple(triple: &str) -> Option<Os> {
+    let parts: Vec<&str> = triple.split('-').collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    let has = |name: &str| parts.contains(&name);
+
+    if has("android") || has("androideabi") {
+        Some(Os::Android)
+    } else if has("ios") {
+        Some(Os::Ios)
+    } else if has("windows") {
+        Some(Os::Windows)
+    } else if has("darwin") || has("macos") {
+        Some(Os::MacOS)
+    } else if has("linux") {
+        Some(Os::Linux)
+    } else if has("freebsd") {
+        Some(Os::FreeBSD)
+    } else if has("openbsd") {
+        Some(Os::OpenBSD)
+    } else if has("netbsd") {
+        Some(Os::NetBSD)
+    } else if has("solaris") || has("illumos") {
+        Some(Os::Solaris)
+    } else {
+        None
+    }
+}
+
 impl Os {
     /// Parse OS from string (case-insensitive) - convenience method
     pub fn parse(s: &str) -> Option<Os> {
@@ -86,6 +124,15 @@ impl Os {
         matches!(self, Os::FreeBSD | Os::OpenBSD | Os::NetBSD | Os::MacOS)
     }
 
+    /// The shell program and the flag that makes it run a command string
+    ///
+    /// Pick this from the target OS rather than the host, e.g. to show what a
+    /// Windows box would run: `("cmd", "/C")` on Windows and `("sh", "-c")`
+    /// everywhere else.
+    pub fn shell_invocation(&self) -> (&'static str, &'static str) {
+        Shell::default_for(*self).invocation()
+    }
+
     /// Get all supported OS variants
     pub fn all() -> &'static [Os] {
         &[
@@ -102,6 +149,48 @@ impl Os {
     }
 }
 
+/// Shells a translated command can be run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    /// Windows cmd.exe
+    Cmd,
+    /// PowerShell (Windows PowerShell or pwsh)
+    PowerShell,
+    /// POSIX shell
+    Sh,
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shell::Cmd => write!(f, "cmd"),
+            Shell::PowerShell => write!(f, "pwsh"),
+            Shell::Sh => write!(f, "sh"),
+        }
+    }
+}
+
+impl Shell {
+    /// The shell commands for an OS are written for by default
+    pub fn default_for(os: Os) -> Shell {
+        if os == Os::Windows {
+            Shell::Cmd
+        } else {
+            Shell::Sh
+        }
+    }
+
+    /// The program and the flag that makes it run a command string
+    pub fn invocation(&self) -> (&'static str, &'static str) {
+        match self {
+            Shell::Cmd => ("cmd", "/C"),
+            Shell::PowerShell => ("pwsh", "-Command"),
+            Shell::Sh => ("sh", "-c"),
+        }
+    }
+}
+
 /// Detect the current operating system at runtime
 #[cfg(target_os = "windows")]
 pub fn detect_os() -> Os {
@@ -167,6 +256,60 @@ pub fn detect_os() -> Os {
     Os::Unknown
 }
 
+/// Check whether a command's binary can be found on `PATH`
+///
+/// Use this before running a translated command to warn when the target
+/// tool (e.g. `ss`) isn't installed. Paths containing a separator are
+/// checked directly instead of searched for.
+pub fn which_available(cmd: &str) -> bool {
+    match std::env::var_os("PATH") {
+        Some(path) => which_available_in(cmd, &path),
+        None => false,
+    }
+}
+
+/// [`which_available`] against an explicit `PATH`-style list of directories
+///
+/// On Windows each `PATHEXT` extension (`.exe`, `.bat`, ...) is tried as well.
+pub fn which_available_in(cmd: &str, path: &std::ffi::OsStr) -> bool {
+    let name = cmd.split_whitespace().next().unwrap_or("");
+    if name.is_empty() {
+        return false;
+    }
+    if name.contains('/') || name.contains(std::path::MAIN_SEPARATOR) {
+        return is_executable(std::path::Path::new(name));
+    }
+    std::env::split_paths(path).any(|dir| {
+        executable_names(name).iter().any(|candidate| is_executable(&dir.join(candidate)))
+    })
+}
+
+/// File names a command may have on disk
+#[cfg(windows)]
+fn executable_names(name: &str) -> Vec<String> {
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    std::iter::once(name.to_string())
+        .chain(extensions.split(';').filter(|e| !e.is_empty()).map(|e| format!("{}{}", name, e)))
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn executable_names(name: &str) -> Vec<String> {
+    vec![name.to_string()]
+}
+
+/// Check that a path is a file the current user can execute
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +333,32 @@ mod tests {
         assert_eq!(Os::parse("invalid"), None);
     }
 
+    #[test]
+    fn test_os_parse_aliases_and_triples() {
+        let cases = [
+            ("win32", Some(Os::Windows)),
+            ("Win64", Some(Os::Windows)),
+            ("gnu/linux", Some(Os::Linux)),
+            ("osx", Some(Os::MacOS)),
+            ("apple", Some(Os::MacOS)),
+            ("bsd", Some(Os::FreeBSD)),
+            ("sunos", Some(Os::Solaris)),
+            ("x86_64-pc-windows-msvc", Some(Os::Windows)),
+            ("x86_64-pc-windows", Some(Os::Windows)),
+            ("aarch64-apple-darwin", Some(Os::MacOS)),
+            ("aarch64-apple-ios", Some(Os::Ios)),
+            ("x86_64-unknown-linux-gnu", Some(Os::Linux)),
+            ("aarch64-linux-android", Some(Os::Android)),
+            ("x86_64-unknown-freebsd", Some(Os::FreeBSD)),
+            ("x86_64-unknown-illumos", Some(Os::Solaris)),
+            ("wasm32-unknown-unknown", None),
+            ("plan9", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(Os::parse(input), expected, "input: {}", input);
+        }
+    }
+
     #[test]
     fn test_os_is_unix_like() {
         assert!(!Os::Windows.is_unix_like());
@@ -221,4 +390,57 @@ mod tests {
         // Just make sure it doesn't panic and returns a valid OS
         assert!(Os::all().contains(&os) || os == Os::Unknown);
     }
+
+    #[test]
+    fn test_shell_default_for() {
+        assert_eq!(Shell::default_for(Os::Windows), Shell::Cmd);
+        assert_eq!(Shell::default_for(Os::Linux), Shell::Sh);
+        assert_eq!(Shell::PowerShell.to_string(), "pwsh");
+    }
+
+    #[test]
+    fn test_os_serde_round_trip_through_parse() {
+        for os in Os::all().iter().chain([&Os::Unknown]) {
+            let json = serde_json::to_string(os).unwrap();
+            let name: String = serde_json::from_str(&json).unwrap();
+            assert_eq!(name, name.to_lowercase());
+            assert_eq!(Os::parse(&name), Some(*os));
+            assert_eq!(serde_json::from_str::<Os>(&json).unwrap(), *os);
+        }
+        assert_eq!(serde_json::to_string(&Os::MacOS).unwrap(), "\"macos\"");
+    }
+
+    #[test]
+    fn test_shell_invocation() {
+        assert_eq!(Os::Windows.shell_invocation(), ("cmd", "/C"));
+        for os in [Os::Linux, Os::MacOS, Os::FreeBSD, Os::OpenBSD, Os::NetBSD, Os::Solaris, Os::Android, Os::Ios, Os::Unknown] {
+            assert_eq!(os.shell_invocation(), ("sh", "-c"), "{}", os);
+        }
+        assert_eq!(Shell::PowerShell.invocation(), ("pwsh", "-Command"));
+    }
+
+    #[test]
+    fn test_which_available_in() {
+        let dir = std::env::temp_dir().join(format!("cmdx-which-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let tool = dir.join(if cfg!(windows) { "fake-tool.exe" } else { "fake-tool" });
+        std::fs::write(&tool, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        std::fs::write(dir.join("not-executable.txt"), "").unwrap();
+
+        let path = std::env::join_paths([dir.clone()]).unwrap();
+        assert!(which_available_in("fake-tool", &path));
+        assert!(which_available_in("fake-tool --version", &path));
+        assert!(!which_available_in("missing-tool", &path));
+        assert!(!which_available_in("", &path));
+        #[cfg(unix)]
+        assert!(!which_available_in("not-executable.txt", &path));
+        assert!(which_available_in(tool.to_str().unwrap(), std::ffi::OsStr::new("")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,927 @@
+//! Package manager command translation module
+//!
+//! This module translates package management commands between package managers,
+//! e.g. `apt install -y vim` to `dnf install -y vim` or `pacman -S --noconfirm vim`.
+//!
+//! ## Examples
+//!
+//! ```
+//! use cmdx::{translate_package_command, PackageManager};
+//!
+//! let result = translate_package_command("apt install -y vim", PackageManager::Apt, PackageManager::Dnf);
+//! assert_eq!(result.unwrap().command, "dnf install -y vim");
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::engine::{split_compound_command, COMPOUND_OPERATORS};
+
+/// Supported package managers
+///
+/// Serialized as the lowercase name accepted by [`PackageManager::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Apt,
+    Dnf,
+    Yum,
+    Pacman,
+    Zypper,
+    Apk,
+    Brew,
+    Choco,
+    Winget,
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.command())
+    }
+}
+
+/// Error returned when parsing an invalid package manager string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePackageManagerError(String);
+
+impl fmt::Display for ParsePackageManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown package manager: '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParsePackageManagerError {}
+
+impl FromStr for PackageManager {
+    type Err = ParsePackageManagerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "apt" | "apt-get" => Ok(PackageManager::Apt),
+            "dnf" => Ok(PackageManager::Dnf),
+            "yum" => Ok(PackageManager::Yum),
+            "pacman" => Ok(PackageManager::Pacman),
+            "zypper" => Ok(PackageManager::Zypper),
+            "apk" => Ok(PackageManager::Apk),
+            "brew" => Ok(PackageManager::Brew),
+            "choco" | "chocolatey" => Ok(PackageManager::Choco),
+            "winget" => Ok(PackageManager::Winget),
+            _ => Err(ParsePackageManagerError(s.to_string())),
+        }
+    }
+}
+
+impl PackageManager {
+    /// Parse package manager from string (case-insensitive) - convenience method
+    pub fn parse(s: &str) -> Option<PackageManager> {
+        s.parse().ok()
+    }
+
+    /// The executable name of this package manager
+    pub fn command(&self) -> &'static str {
+        match self {
+            PackageManager::Apt => "apt",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Yum => "yum",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Zypper => "zypper",
+            PackageManager::Apk => "apk",
+            PackageManager::Brew => "brew",
+            PackageManager::Choco => "choco",
+            PackageManager::Winget => "winget",
+        }
+    }
+
+    /// Whether modifying operations need root privileges
+    pub fn requires_sudo(&self) -> bool {
+        matches!(
+            self,
+            PackageManager::Apt
+                | PackageManager::Dnf
+                | PackageManager::Yum
+                | PackageManager::Pacman
+                | PackageManager::Zypper
+                | PackageManager::Apk
+        )
+    }
+
+    /// The package manager of a distribution or OS, e.g. `ubuntu` or `fedora`
+    ///
+    /// Package manager names (`dnf`, `apt-get`) are accepted as well.
+    pub fn for_distro(distro: &str) -> Option<PackageManager> {
+        match distro.to_lowercase().as_str() {
+            "debian" | "ubuntu" | "mint" | "linuxmint" | "pop" | "pop_os" | "kali" | "raspbian" => Some(PackageManager::Apt),
+            "fedora" | "rhel" | "centos" | "rocky" | "almalinux" | "alma" => Some(PackageManager::Dnf),
+            "amzn" | "amazonlinux" => Some(PackageManager::Yum),
+            "arch" | "archlinux" | "manjaro" | "endeavouros" => Some(PackageManager::Pacman),
+            "opensuse" | "suse" | "sles" | "opensuse-leap" | "opensuse-tumbleweed" => Some(PackageManager::Zypper),
+            "alpine" => Some(PackageManager::Apk),
+            "macos" | "darwin" => Some(PackageManager::Brew),
+            "windows" => Some(PackageManager::Winget),
+            other => PackageManager::parse(other),
+        }
+    }
+
+    /// Get all supported package managers
+    pub fn all() -> &'static [PackageManager] {
+        &[
+            PackageManager::Apt,
+            PackageManager::Dnf,
+            PackageManager::Yum,
+            PackageManager::Pacman,
+            PackageManager::Zypper,
+            PackageManager::Apk,
+            PackageManager::Brew,
+            PackageManager::Choco,
+            PackageManager::Winget,
+        ]
+    }
+
+    /// Flag that answers yes to all prompts, if the manager has one
+    fn assume_yes_flag(&self) -> Option<&'static str> {
+        match self {
+            PackageManager::Apt | PackageManager::Dnf | PackageManager::Yum | PackageManager::Choco => Some("-y"),
+            PackageManager::Pacman => Some("--noconfirm"),
+            PackageManager::Zypper => Some("-n"),
+            PackageManager::Apk | PackageManager::Brew | PackageManager::Winget => None,
+        }
+    }
+}
+
+/// Package management operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageOperation {
+    /// Install packages
+    Install,
+    /// Remove packages
+    Remove,
+    /// Refresh the package index
+    Update,
+    /// Upgrade installed packages
+    Upgrade,
+    /// Search for packages
+    Search,
+    /// Clean the package cache
+    Clean,
+}
+
+impl PackageOperation {
+    /// Whether this operation modifies the system
+    fn is_modifying(&self) -> bool {
+        !matches!(self, PackageOperation::Search)
+    }
+}
+
+/// Result of a package command translation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageTranslation {
+    /// The translated command
+    pub command: String,
+    /// Original command
+    pub original: String,
+    /// Source package manager
+    pub from: PackageManager,
+    /// Target package manager
+    pub to: PackageManager,
+    /// Whether the translated command needs root privileges
+    pub requires_sudo: bool,
+    /// Warnings or notes about the translation
+    pub warnings: Vec<String>,
+}
+
+impl PackageTranslation {
+    pub fn new(command: String, original: String, from: PackageManager, to: PackageManager) -> Self {
+        Self {
+            command,
+            original,
+            from,
+            to,
+            requires_sudo: false,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for PackageTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.command)
+    }
+}
+
+/// Errors that can occur during package command translation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PackageError {
+    /// Empty command
+    EmptyCommand,
+    /// Command is not an invocation of the expected package manager
+    NotPackageManagerCommand(String),
+    /// Operation not recognized or not available on the target
+    UnsupportedOperation(String),
+}
+
+impl fmt::Display for PackageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageError::EmptyCommand => write!(f, "Empty command provided"),
+            PackageError::NotPackageManagerCommand(cmd) => {
+                write!(f, "Not a package manager command: '{}'", cmd)
+            }
+            PackageError::UnsupportedOperation(op) => {
+                write!(f, "Unsupported package operation: '{}'", op)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackageError {}
+
+/// A package command broken into its parts
+struct ParsedPackageCommand {
+    sudo: bool,
+    operation: PackageOperation,
+    assume_yes: bool,
+    packages: Vec<String>,
+    flags: Vec<String>,
+}
+
+/// Map a subcommand of the given package manager to an operation
+fn parse_operation(manager: PackageManager, subcommand: &str) -> Option<PackageOperation> {
+    use PackageOperation::*;
+
+    let op = match (manager, subcommand) {
+        (PackageManager::Apt, "install") => Install,
+        (PackageManager::Apt, "remove" | "purge") => Remove,
+        (PackageManager::Apt, "update") => Update,
+        (PackageManager::Apt, "upgrade" | "dist-upgrade" | "full-upgrade") => Upgrade,
+        (PackageManager::Apt, "search") => Search,
+        (PackageManager::Apt, "clean" | "autoclean") => Clean,
+        (PackageManager::Dnf | PackageManager::Yum, "install") => Install,
+        (PackageManager::Dnf | PackageManager::Yum, "remove" | "erase") => Remove,
+        (PackageManager::Dnf | PackageManager::Yum, "makecache" | "check-update") => Update,
+        (PackageManager::Dnf | PackageManager::Yum, "upgrade" | "update") => Upgrade,
+        (PackageManager::Dnf | PackageManager::Yum, "search") => Search,
+        (PackageManager::Dnf | PackageManager::Yum, "clean") => Clean,
+        (PackageManager::Pacman, "-S") => Install,
+        (PackageManager::Pacman, "-R" | "-Rs" | "-Rns") => Remove,
+        (PackageManager::Pacman, "-Sy") => Update,
+        (PackageManager::Pacman, "-Syu" | "-Su") => Upgrade,
+        (PackageManager::Pacman, "-Ss") => Search,
+        (PackageManager::Pacman, "-Sc" | "-Scc") => Clean,
+        (PackageManager::Zypper, "install" | "in") => Install,
+        (PackageManager::Zypper, "remove" | "rm") => Remove,
+        (PackageManager::Zypper, "refresh" | "ref") => Update,
+        (PackageManager::Zypper, "update" | "up" | "dist-upgrade" | "dup") => Upgrade,
+        (PackageManager::Zypper, "search" | "se") => Search,
+        (PackageManager::Zypper, "clean") => Clean,
+        (PackageManager::Apk, "add") => Install,
+        (PackageManager::Apk, "del") => Remove,
+        (PackageManager::Apk, "update") => Update,
+        (PackageManager::Apk, "upgrade") => Upgrade,
+        (PackageManager::Apk, "search") => Search,
+        (PackageManager::Apk, "cache") => Clean,
+        (PackageManager::Brew, "install") => Install,
+        (PackageManager::Brew, "uninstall" | "remove") => Remove,
+        (PackageManager::Brew, "update") => Update,
+        (PackageManager::Brew, "upgrade") => Upgrade,
+        (PackageManager::Brew, "search") => Search,
+        (PackageManager::Brew, "cleanup") => Clean,
+        (PackageManager::Choco, "install") => Install,
+        (PackageManager::Choco, "uninstall") => Remove,
+        (PackageManager::Choco, "upgrade") => Upgrade,
+        (PackageManager::Choco, "search") => Search,
+        (PackageManager::Winget, "install") => Install,
+        (PackageManager::Winget, "uninstall") => Remove,
+        (PackageManager::Winget, "source") => Update,
+        (PackageManager::Winget, "upgrade") => Upgrade,
+        (PackageManager::Winget, "search") => Search,
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Build the subcommand for an operation on the given package manager
+fn operation_command(manager: PackageManager, operation: PackageOperation) -> Option<&'static str> {
+    use PackageOperation::*;
+
+    let cmd = match (manager, operation) {
+        (PackageManager::Apt, Install) => "install",
+        (PackageManager::Apt, Remove) => "remove",
+        (PackageManager::Apt, Update) => "update",
+        (PackageManager::Apt, Upgrade) => "upgrade",
+        (PackageManager::Apt, Search) => "search",
+        (PackageManager::Apt, Clean) => "clean",
+        (PackageManager::Dnf | PackageManager::Yum, Install) => "install",
+        (PackageManager::Dnf | PackageManager::Yum, Remove) => "remove",
+        (PackageManager::Dnf | PackageManager::Yum, Update) => "makecache",
+        (PackageManager::Dnf | PackageManager::Yum, Upgrade) => "upgrade",
+        (PackageManager::Dnf | PackageManager::Yum, Search) => "search",
+        (PackageManager::Dnf | PackageManager::Yum, Clean) => "clean all",
+        (PackageManager::Pacman, Install) => "-S",
+        (PackageManager::Pacman, Remove) => "-R",
+        (PackageManager::Pacman, Update) => "-Sy",
+        (PackageManager::Pacman, Upgrade) => "-Syu",
+        (PackageManager::Pacman, Search) => "-Ss",
+        (PackageManager::Pacman, Clean) => "-Sc",
+        (PackageManager::Zypper, Install) => "install",
+        (PackageManager::Zypper, Remove) => "remove",
+        (PackageManager::Zypper, Update) => "refresh",
+        (PackageManager::Zypper, Upgrade) => "update",
+        (PackageManager::Zypper, Search) => "search",
+        (PackageManager::Zypper, Clean) => "clean",
+        (PackageManager::Apk, Install) => "add",
+        (PackageManager::Apk, Remove) => "del",
+        (PackageManager::Apk, Update) => "update",
+        (PackageManager::Apk, Upgrade) => "upgrade",
+        (PackageManager::Apk, Search) => "search",
+        (PackageManager::Apk, Clean) => "cache clean",
+        (PackageManager::Brew, Install) => "install",
+        (PackageManager::Brew, Remove) => "uninstall",
+        (PackageManager::Brew, Update) => "update",
+        (PackageManager::Brew, Upgrade) => "upgrade",
+        (PackageManager::Brew, Search) => "search",
+        (PackageManager::Brew, Clean) => "cleanup",
+        (PackageManager::Choco, Install) => "install",
+        (PackageManager::Choco, Remove) => "uninstall",
+        (PackageManager::Choco, Upgrade) => "upgrade all",
+        (PackageManager::Choco, Search) => "search",
+        (PackageManager::Winget, Install) => "install",
+        (PackageManager::Winget, Remove) => "uninstall",
+        (PackageManager::Winget, Update) => "source update",
+        (PackageManager::Winget, Upgrade) => "upgrade --all",
+        (PackageManager::Winget, Search) => "search",
+        (PackageManager::Choco | PackageManager::Winget, _) => return None,
+    };
+    Some(cmd)
+}
+
+/// Equivalents of a flag on other package managers
+type FlagEquivalents = &'static [(PackageManager, &'static str)];
+
+/// Manager-specific flags: (manager, flag, equivalents on other managers, note when dropped)
+const PACKAGE_FLAG_MAPPINGS: &[(PackageManager, &str, FlagEquivalents, &str)] = &[
+    (
+        PackageManager::Apk,
+        "--no-cache",
+        &[],
+        "apk --no-cache has no equivalent; clean the package cache after installing instead",
+    ),
+    (
+        PackageManager::Apt,
+        "--no-install-recommends",
+        &[
+            (PackageManager::Dnf, "--setopt=install_weak_deps=False"),
+            (PackageManager::Yum, "--setopt=install_weak_deps=False"),
+            (PackageManager::Zypper, "--no-recommends"),
+        ],
+        "recommended packages may be installed",
+    ),
+    (
+        PackageManager::Dnf,
+        "--setopt=install_weak_deps=False",
+        &[
+            (PackageManager::Apt, "--no-install-recommends"),
+            (PackageManager::Zypper, "--no-recommends"),
+        ],
+        "weak dependencies may be installed",
+    ),
+    (
+        PackageManager::Zypper,
+        "--no-recommends",
+        &[
+            (PackageManager::Apt, "--no-install-recommends"),
+            (PackageManager::Dnf, "--setopt=install_weak_deps=False"),
+        ],
+        "recommended packages may be installed",
+    ),
+];
+
+/// Map a manager-specific flag to the target manager
+///
+/// Returns the equivalent flag, or a warning explaining why it was dropped.
+fn translate_package_flag(flag: &str, from: PackageManager, to: PackageManager) -> Result<String, String> {
+    if from == to {
+        return Ok(flag.to_string());
+    }
+
+    match PACKAGE_FLAG_MAPPINGS
+        .iter()
+        .find(|(manager, name, _, _)| *manager == from && *name == flag)
+    {
+        Some((_, _, equivalents, note)) => equivalents
+            .iter()
+            .find(|(manager, _)| *manager == to)
+            .map(|(_, target)| target.to_string())
+            .ok_or_else(|| format!("Flag '{}' dropped for {}: {}", flag, to, note)),
+        None => Err(format!("Flag '{}' was dropped", flag)),
+    }
+}
+
+/// Split off a leading `sudo`, returning whether it was present and the rest
+fn strip_sudo(input: &str) -> (bool, &str) {
+    let trimmed = input.trim();
+    match trimmed.strip_prefix("sudo ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, trimmed),
+    }
+}
+
+/// Strip a leading `yes |` auto-confirm pipe, returning the piped command
+fn strip_yes_pipe(input: &str) -> Option<&str> {
+    let rest = input.trim().strip_prefix("yes")?.trim_start().strip_prefix('|')?;
+    Some(rest.trim_start())
+}
+
+/// Identify repository-management commands and the package manager they belong to
+///
+/// These (`add-apt-repository`, `apt-key`, `dnf config-manager`, ...) have no
+/// cross-distro equivalent, so they are recognized but never rewritten.
+fn repository_command_manager(input: &str) -> Option<PackageManager> {
+    let (_, command) = strip_sudo(input);
+    let mut tokens = command.split_whitespace();
+    match (tokens.next()?, tokens.next()) {
+        ("add-apt-repository" | "apt-key", _) => Some(PackageManager::Apt),
+        ("yum-config-manager", _) => Some(PackageManager::Yum),
+        ("dnf", Some("config-manager")) => Some(PackageManager::Dnf),
+        ("pacman-key", _) => Some(PackageManager::Pacman),
+        ("zypper", Some("addrepo" | "ar" | "removerepo" | "rr")) => Some(PackageManager::Zypper),
+        ("brew", Some("tap" | "untap")) => Some(PackageManager::Brew),
+        ("choco", Some("source")) => Some(PackageManager::Choco),
+        _ => None,
+    }
+}
+
+/// Detect which package manager a command line invokes
+///
+/// Repository-management helpers such as `add-apt-repository` count as
+/// their package manager.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{detect_package_manager, PackageManager};
+///
+/// assert_eq!(detect_package_manager("sudo apt-get install vim"), Some(PackageManager::Apt));
+/// assert_eq!(detect_package_manager("add-apt-repository ppa:x/y"), Some(PackageManager::Apt));
+/// assert_eq!(detect_package_manager("ls -la"), None);
+/// ```
+pub fn detect_package_manager(input: &str) -> Option<PackageManager> {
+    let input = strip_yes_pipe(input).unwrap_or(input);
+    if let Some(manager) = repository_command_manager(input) {
+        return Some(manager);
+    }
+    let (_, command) = strip_sudo(input);
+    PackageManager::parse(command.split_whitespace().next()?)
+}
+
+/// Parse a command line for the given package manager
+fn parse_package_command(input: &str, manager: PackageManager) -> Result<ParsedPackageCommand, PackageError> {
+    let mut tokens = input.split_whitespace().peekable();
+
+    let sudo = tokens.peek() == Some(&"sudo");
+    if sudo {
+        tokens.next();
+    }
+
+    let command = tokens.next().ok_or(PackageError::EmptyCommand)?;
+    if PackageManager::parse(command) != Some(manager) {
+        return Err(PackageError::NotPackageManagerCommand(input.trim().to_string()));
+    }
+
+    let mut operation = None;
+    let mut assume_yes = false;
+    let mut packages = Vec::new();
+    let mut flags = Vec::new();
+
+    for token in tokens {
+        match token {
+            "-y" | "--yes" | "--assume-yes" | "--assumeyes" | "--noconfirm" | "--non-interactive" => {
+                assume_yes = true;
+            }
+            // zypper's global non-interactive flag
+            "-n" if manager == PackageManager::Zypper && operation.is_none() => assume_yes = true,
+            _ if operation.is_none() => {
+                if let Some(op) = parse_operation(manager, token) {
+                    operation = Some(op);
+                } else if token.starts_with('-') {
+                    flags.push(token.to_string());
+                } else {
+                    return Err(PackageError::UnsupportedOperation(token.to_string()));
+                }
+            }
+            // Trailing words of multi-word subcommands (`dnf clean all`, `apk cache clean`)
+            "all" | "clean" if operation == Some(PackageOperation::Clean) => {}
+            "update" if manager == PackageManager::Winget && operation == Some(PackageOperation::Update) => {}
+            _ if token.starts_with('-') => flags.push(token.to_string()),
+            _ => packages.push(token.to_string()),
+        }
+    }
+
+    let operation = operation.ok_or_else(|| PackageError::UnsupportedOperation(String::new()))?;
+
+    Ok(ParsedPackageCommand {
+        sudo,
+        operation,
+        assume_yes,
+        packages,
+        flags,
+    })
+}
+
+/// Translate a package manager command to another package manager
+///
+/// # Arguments
+///
+/// * `input` - The package command to translate (may start with `sudo`)
+/// * `from` - The source package manager
+/// * `to` - The target package manager
+///
+/// # Returns
+///
+/// * `Ok(PackageTranslation)` - The translated command
+/// * `Err(PackageError)` - Error if translation fails
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_package_command, PackageManager};
+///
+/// let result = translate_package_command("sudo apt install -y git", PackageManager::Apt, PackageManager::Pacman);
+/// assert_eq!(result.unwrap().command, "sudo pacman -S --noconfirm git");
+/// ```
+pub fn translate_package_command(
+    input: &str,
+    from: PackageManager,
+    to: PackageManager,
+) -> Result<PackageTranslation, PackageError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(PackageError::EmptyCommand);
+    }
+
+    if repository_command_manager(trimmed) == Some(from) {
+        let mut result = PackageTranslation::new(trimmed.to_string(), trimmed.to_string(), from, to);
+        result.requires_sudo = from.requires_sudo();
+        if from != to {
+            result.warnings.push(format!(
+                "Repository management is distro-specific; manual steps required to configure {} repositories",
+                to
+            ));
+        }
+        return Ok(result);
+    }
+
+    // `yes | cmd` answers every prompt; fold it into the assume-yes flag
+    let (yes_pipe, command) = match strip_yes_pipe(trimmed) {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+
+    let mut parsed = parse_package_command(command, from)?;
+    parsed.assume_yes |= yes_pipe;
+
+    let mut result = PackageTranslation::new(String::new(), trimmed.to_string(), from, to);
+    result.requires_sudo = to.requires_sudo() && parsed.operation.is_modifying();
+
+    let subcommand = operation_command(to, parsed.operation).ok_or_else(|| {
+        PackageError::UnsupportedOperation(format!("{:?} is not available in {}", parsed.operation, to))
+    })?;
+
+    let mut parts = Vec::new();
+    if parsed.sudo {
+        if result.requires_sudo {
+            parts.push("sudo".to_string());
+        } else {
+            result.warnings.push(format!("sudo dropped: {} does not need root for this operation", to));
+        }
+    }
+    parts.push(to.command().to_string());
+
+    if parsed.assume_yes {
+        match to.assume_yes_flag() {
+            // zypper's -n is a global option placed before the subcommand
+            Some(flag) if to == PackageManager::Zypper => parts.push(flag.to_string()),
+            Some(_) => {}
+            None if to == PackageManager::Apk => {}
+            None => result.warnings.push(format!("{} has no assume-yes flag", to)),
+        }
+    }
+
+    parts.push(subcommand.to_string());
+
+    if parsed.assume_yes && to != PackageManager::Zypper {
+        if let Some(flag) = to.assume_yes_flag() {
+            parts.push(flag.to_string());
+        }
+    }
+
+    for flag in &parsed.flags {
+        match translate_package_flag(flag, from, to) {
+            Ok(translated) => parts.push(translated),
+            Err(warning) => result.warnings.push(warning),
+        }
+    }
+
+    if to == PackageManager::Apk && from != PackageManager::Apk && parsed.operation == PackageOperation::Install {
+        result
+            .warnings
+            .push("In Docker images consider 'apk add --no-cache' to avoid keeping the package index".to_string());
+    }
+
+    parts.extend(parsed.packages);
+    result.command = parts.join(" ");
+
+    Ok(result)
+}
+
+/// Translate a package command, detecting the source package manager
+///
+/// Returns `Err(PackageError::NotPackageManagerCommand)` when the command
+/// doesn't invoke a known package manager.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_package_command_auto, PackageManager};
+///
+/// let result = translate_package_command_auto("dnf install -y git", PackageManager::Apt);
+/// assert_eq!(result.unwrap().command, "apt install -y git");
+/// ```
+pub fn translate_package_command_auto(input: &str, to: PackageManager) -> Result<PackageTranslation, PackageError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(PackageError::EmptyCommand);
+    }
+    let from = detect_package_manager(trimmed)
+        .ok_or_else(|| PackageError::NotPackageManagerCommand(trimmed.to_string()))?;
+    translate_package_command(trimmed, from, to)
+}
+
+/// Translate a compound line of package commands, e.g. `apt update && apt install -y vim`
+///
+/// Each segment is translated with [`translate_package_command`]; segments that
+/// aren't commands of the source package manager are passed through unchanged,
+/// as are package commands whose operation has no equivalent, with a warning.
+/// Warnings are aggregated and `requires_sudo` is set if any segment needs it.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_package_compound, PackageManager};
+///
+/// let result = translate_package_compound(
+///     "apt update && apt install -y vim && apt clean",
+///     PackageManager::Apt,
+///     PackageManager::Dnf,
+/// );
+/// assert_eq!(result.unwrap().command, "dnf makecache && dnf install -y vim && dnf clean all");
+/// ```
+pub fn translate_package_compound(
+    input: &str,
+    from: PackageManager,
+    to: PackageManager,
+) -> Result<PackageTranslation, PackageError> {
+    translate_package_compound_with(input, from, to, str::to_string)
+}
+
+/// [`translate_package_compound`] with a translation for everything else on the line
+///
+/// `translate_other` is given the operators and the segments that aren't
+/// commands of the source package manager, e.g. to move them to another OS.
+pub(crate) fn translate_package_compound_with(
+    input: &str,
+    from: PackageManager,
+    to: PackageManager,
+    mut translate_other: impl FnMut(&str) -> String,
+) -> Result<PackageTranslation, PackageError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(PackageError::EmptyCommand);
+    }
+
+    let mut result = PackageTranslation::new(String::new(), trimmed.to_string(), from, to);
+    let mut translated_parts = Vec::new();
+
+    let parts = split_compound_command(trimmed);
+    let mut yes_pipe = false;
+
+    for (i, part) in parts.iter().enumerate() {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        if COMPOUND_OPERATORS.contains(&part) {
+            if !yes_pipe {
+                translated_parts.push(translate_other(part));
+            }
+            continue;
+        }
+        // `yes |` before a package command is folded into its assume-yes flag
+        if part == "yes" && parts.get(i + 1).map(|p| p.trim()) == Some("|") {
+            let next = parts.get(i + 2).map(|p| p.trim()).unwrap_or("");
+            if detect_package_manager(next) == Some(from) {
+                yes_pipe = true;
+                continue;
+            }
+        }
+
+        let segment = if yes_pipe { format!("yes | {}", part) } else { part.to_string() };
+        yes_pipe = false;
+
+        match translate_package_command(&segment, from, to) {
+            Ok(translated) => {
+                result.requires_sudo |= translated.requires_sudo;
+                result.warnings.extend(translated.warnings);
+                translated_parts.push(translated.command);
+            }
+            Err(PackageError::NotPackageManagerCommand(_)) => translated_parts.push(translate_other(part)),
+            Err(PackageError::UnsupportedOperation(op)) => {
+                result.warnings.push(format!(
+                    "Package operation '{}' has no {} equivalent; '{}' passed through unchanged",
+                    op, to, segment
+                ));
+                translated_parts.push(segment);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    result.command = translated_parts.join(" ");
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_manager_for_distro() {
+        assert_eq!(PackageManager::for_distro("Ubuntu"), Some(PackageManager::Apt));
+        assert_eq!(PackageManager::for_distro("fedora"), Some(PackageManager::Dnf));
+        assert_eq!(PackageManager::for_distro("arch"), Some(PackageManager::Pacman));
+        assert_eq!(PackageManager::for_distro("alpine"), Some(PackageManager::Apk));
+        assert_eq!(PackageManager::for_distro("apt-get"), Some(PackageManager::Apt));
+        assert_eq!(PackageManager::for_distro("plan9"), None);
+    }
+
+    #[test]
+    fn test_package_manager_from_str() {
+        assert_eq!("apt".parse::<PackageManager>(), Ok(PackageManager::Apt));
+        assert_eq!("apt-get".parse::<PackageManager>(), Ok(PackageManager::Apt));
+        assert_eq!("DNF".parse::<PackageManager>(), Ok(PackageManager::Dnf));
+        assert!("npm".parse::<PackageManager>().is_err());
+    }
+
+    #[test]
+    fn test_translate_apt_install_to_dnf() {
+        let result = translate_package_command("apt install -y vim", PackageManager::Apt, PackageManager::Dnf).unwrap();
+        assert_eq!(result.command, "dnf install -y vim");
+        assert!(result.requires_sudo);
+    }
+
+    #[test]
+    fn test_translate_apt_install_to_pacman() {
+        let result = translate_package_command("sudo apt-get install -y git curl", PackageManager::Apt, PackageManager::Pacman).unwrap();
+        assert_eq!(result.command, "sudo pacman -S --noconfirm git curl");
+    }
+
+    #[test]
+    fn test_translate_pacman_to_apt() {
+        let result = translate_package_command("pacman -Syu", PackageManager::Pacman, PackageManager::Apt).unwrap();
+        assert_eq!(result.command, "apt upgrade");
+        let result = translate_package_command("pacman -R vim", PackageManager::Pacman, PackageManager::Apt).unwrap();
+        assert_eq!(result.command, "apt remove vim");
+    }
+
+    #[test]
+    fn test_translate_to_zypper_non_interactive() {
+        let result = translate_package_command("dnf install -y vim", PackageManager::Dnf, PackageManager::Zypper).unwrap();
+        assert_eq!(result.command, "zypper -n install vim");
+    }
+
+    #[test]
+    fn test_translate_sudo_dropped_for_brew() {
+        let result = translate_package_command("sudo apt install vim", PackageManager::Apt, PackageManager::Brew).unwrap();
+        assert_eq!(result.command, "brew install vim");
+        assert!(!result.requires_sudo);
+        assert!(result.warnings.iter().any(|w| w.contains("sudo")));
+    }
+
+    #[test]
+    fn test_translate_not_package_command() {
+        let result = translate_package_command("ls -la", PackageManager::Apt, PackageManager::Dnf);
+        assert!(matches!(result, Err(PackageError::NotPackageManagerCommand(_))));
+    }
+
+    #[test]
+    fn test_translate_unsupported_operation() {
+        let result = translate_package_command("apt clean", PackageManager::Apt, PackageManager::Choco);
+        assert!(matches!(result, Err(PackageError::UnsupportedOperation(_))));
+    }
+
+    #[test]
+    fn test_translate_package_compound_apt_chain_to_dnf() {
+        let result = translate_package_compound(
+            "apt update && apt install -y vim && apt clean",
+            PackageManager::Apt,
+            PackageManager::Dnf,
+        )
+        .unwrap();
+        assert_eq!(result.command, "dnf makecache && dnf install -y vim && dnf clean all");
+        assert!(result.requires_sudo);
+    }
+
+    #[test]
+    fn test_translate_package_compound_passes_other_commands() {
+        let result = translate_package_compound(
+            "apt install -y curl && curl -O https://example.com/x.sh",
+            PackageManager::Apt,
+            PackageManager::Apk,
+        )
+        .unwrap();
+        assert_eq!(result.command, "apk add curl && curl -O https://example.com/x.sh");
+    }
+
+    #[test]
+    fn test_translate_package_compound_passes_unsupported_operations() {
+        let result = translate_package_compound(
+            "apt update && apt autoremove",
+            PackageManager::Apt,
+            PackageManager::Dnf,
+        )
+        .unwrap();
+        assert_eq!(result.command, "dnf makecache && apt autoremove");
+        assert!(result.warnings.iter().any(|w| w.contains("'autoremove'")));
+    }
+
+    #[test]
+    fn test_translate_apk_no_cache_to_apt() {
+        let result = translate_package_command("apk add --no-cache curl", PackageManager::Apk, PackageManager::Apt).unwrap();
+        assert_eq!(result.command, "apt install curl");
+        assert!(result.warnings.iter().any(|w| w.contains("--no-cache")));
+    }
+
+    #[test]
+    fn test_translate_apt_to_apk_suggests_no_cache() {
+        let result = translate_package_command("apt install curl", PackageManager::Apt, PackageManager::Apk).unwrap();
+        assert_eq!(result.command, "apk add curl");
+        assert!(result.warnings.iter().any(|w| w.contains("--no-cache")));
+    }
+
+    #[test]
+    fn test_translate_no_install_recommends() {
+        let result = translate_package_command(
+            "apt install --no-install-recommends curl",
+            PackageManager::Apt,
+            PackageManager::Zypper,
+        )
+        .unwrap();
+        assert_eq!(result.command, "zypper install --no-recommends curl");
+    }
+
+    #[test]
+    fn test_package_manager_serde_round_trip_through_parse() {
+        for manager in PackageManager::all() {
+            let json = serde_json::to_string(manager).unwrap();
+            let name: String = serde_json::from_str(&json).unwrap();
+            assert_eq!(PackageManager::parse(&name), Some(*manager));
+            assert_eq!(serde_json::from_str::<PackageManager>(&json).unwrap(), *manager);
+        }
+    }
+
+    #[test]
+    fn test_repository_commands_pass_through_with_warning() {
+        let result = translate_package_command_auto("sudo add-apt-repository ppa:deadsnakes/ppa", PackageManager::Dnf).unwrap();
+        assert_eq!(result.command, "sudo add-apt-repository ppa:deadsnakes/ppa");
+        assert!(result.warnings.iter().any(|w| w.contains("distro-specific")));
+
+        let result = translate_package_command("apt-key add key.gpg", PackageManager::Apt, PackageManager::Pacman).unwrap();
+        assert_eq!(result.command, "apt-key add key.gpg");
+        assert!(!result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_package_command_auto() {
+        let result = translate_package_command_auto("pacman -S vim", PackageManager::Apt).unwrap();
+        assert_eq!(result.command, "apt install vim");
+        assert!(matches!(
+            translate_package_command_auto("ls -la", PackageManager::Apt),
+            Err(PackageError::NotPackageManagerCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_yes_pipe_folded_into_assume_yes() {
+        let result = translate_package_command("yes | pacman -S vim", PackageManager::Pacman, PackageManager::Apt).unwrap();
+        assert_eq!(result.command, "apt install -y vim");
+
+        let result = translate_package_compound(
+            "apt update && yes | apt install vim",
+            PackageManager::Apt,
+            PackageManager::Pacman,
+        )
+        .unwrap();
+        assert_eq!(result.command, "pacman -Sy && pacman -S --noconfirm vim");
+    }
+}
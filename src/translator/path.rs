@@ -3,6 +3,7 @@
 //! This module provides bidirectional path translation between Windows and Unix-like
 //! operating systems, handling path separators, drive letters, and common path mappings.
 
+use super::env::translate_env_vars;
 use super::os::Os;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -50,6 +51,8 @@ pub enum PathError {
     EmptyPath,
     /// Invalid path format
     InvalidPath(String),
+    /// The source path format could not be determined
+    Ambiguous(String),
 }
 
 impl fmt::Display for PathError {
@@ -57,16 +60,51 @@ impl fmt::Display for PathError {
         match self {
             PathError::EmptyPath => write!(f, "Empty path provided"),
             PathError::InvalidPath(msg) => write!(f, "Invalid path: {}", msg),
+            PathError::Ambiguous(path) => {
+                write!(f, "Ambiguous path format: '{}' is valid on both Windows and Unix", path)
+            }
         }
     }
 }
 
 impl std::error::Error for PathError {}
 
+/// Options controlling how paths are translated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathTranslationOptions {
+    /// Directory that drive letters are mounted under on the Unix side:
+    /// `/mnt` for WSL (the default), `/cygdrive` for Cygwin, or `/` for MSYS
+    pub mount_prefix: String,
+}
+
+impl Default for PathTranslationOptions {
+    fn default() -> Self {
+        Self {
+            mount_prefix: "/mnt".to_string(),
+        }
+    }
+}
+
+/// Mount prefixes recognized when converting a Unix path back to a drive letter
+const KNOWN_MOUNT_PREFIXES: &[&str] = &["/mnt", "/cygdrive", ""];
+
 /// Common drive letter to Unix path mappings
-fn get_drive_mapping(drive: char) -> String {
-    // Use lowercase for the mount point (WSL convention)
-    format!("/mnt/{}", drive.to_ascii_lowercase())
+fn get_drive_mapping(drive: char, mount_prefix: &str) -> String {
+    // Use lowercase for the mount point, as WSL, Cygwin and MSYS all do
+    format!("{}/{}", mount_prefix.trim_end_matches('/'), drive.to_ascii_lowercase())
+}
+
+/// Split a mounted drive path like `/mnt/c/Users` into its drive letter and the rest
+fn split_mounted_drive<'a>(path: &'a str, mount_prefix: &str) -> Option<(char, &'a str)> {
+    let custom = mount_prefix.trim_end_matches('/');
+    std::iter::once(custom)
+        .chain(KNOWN_MOUNT_PREFIXES.iter().copied())
+        .find_map(|prefix| {
+            let rest = path.strip_prefix(prefix)?.strip_prefix('/')?;
+            let drive = rest.chars().next().filter(char::is_ascii_alphabetic)?;
+            let after = &rest[1..];
+            (after.is_empty() || after.starts_with('/')).then_some((drive, after))
+        })
 }
 
 /// Check if a path looks like a Windows path
@@ -92,7 +130,7 @@ pub fn is_unix_path(path: &str) -> bool {
 }
 
 /// Translate a Windows path to Unix path
-fn windows_to_unix(path: &str, result: &mut PathTranslation) -> String {
+fn windows_to_unix(path: &str, opts: &PathTranslationOptions, result: &mut PathTranslation) -> String {
     let mut unix_path = path.to_string();
     
     // Handle drive letter (C:\Users -> /mnt/c/Users)
@@ -100,7 +138,7 @@ fn windows_to_unix(path: &str, result: &mut PathTranslation) -> String {
         let chars: Vec<char> = unix_path.chars().collect();
         if chars[0].is_ascii_alphabetic() && chars[1] == ':' {
             let drive = chars[0];
-            let mount_point = get_drive_mapping(drive);
+            let mount_point = get_drive_mapping(drive, &opts.mount_prefix);
             unix_path = format!("{}{}", mount_point, &unix_path[2..]);
             result.drive_translated = true;
         }
@@ -132,22 +170,13 @@ fn windows_to_unix(path: &str, result: &mut PathTranslation) -> String {
 }
 
 /// Translate a Unix path to Windows path
-fn unix_to_windows(path: &str, result: &mut PathTranslation) -> String {
+fn unix_to_windows(path: &str, opts: &PathTranslationOptions, result: &mut PathTranslation) -> String {
     let mut windows_path = path.to_string();
     
-    // Handle /mnt/X/ paths (convert to X:\)
-    if windows_path.starts_with("/mnt/") && windows_path.len() >= 6 {
-        let drive_char = windows_path.chars().nth(5);
-        if let Some(drive) = drive_char {
-            if drive.is_ascii_alphabetic() {
-                // Check if it's followed by / or end of string
-                let after_drive = windows_path.chars().nth(6);
-                if after_drive.is_none() || after_drive == Some('/') {
-                    windows_path = format!("{}:{}", drive.to_ascii_uppercase(), &windows_path[6..]);
-                    result.drive_translated = true;
-                }
-            }
-        }
+    // Handle /mnt/X, /cygdrive/X and /X paths (convert to X:\)
+    if let Some((drive, rest)) = split_mounted_drive(path, &opts.mount_prefix) {
+        windows_path = format!("{}:{}", drive.to_ascii_uppercase(), rest);
+        result.drive_translated = true;
     }
     // Handle /home/username -> C:\Users\username (common mapping)
     else if windows_path.starts_with("/home/") {
@@ -223,6 +252,30 @@ pub fn translate_path(
     path: &str,
     from_os: Os,
     to_os: Os,
+) -> Result<PathTranslation, PathError> {
+    translate_path_with_options(path, from_os, to_os, &PathTranslationOptions::default())
+}
+
+/// Translate a file path using the given [`PathTranslationOptions`]
+///
+/// Drive letters are mounted under `opts.mount_prefix` when translating to
+/// Unix. The reverse direction recognizes `/mnt/c`, `/cygdrive/c` and `/c`
+/// regardless of the prefix chosen.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_path_with_options, Os, PathTranslationOptions};
+///
+/// let opts = PathTranslationOptions { mount_prefix: "/cygdrive".to_string() };
+/// let result = translate_path_with_options("C:\\Users", Os::Windows, Os::Linux, &opts).unwrap();
+/// assert_eq!(result.path, "/cygdrive/c/Users");
+/// ```
+pub fn translate_path_with_options(
+    path: &str,
+    from_os: Os,
+    to_os: Os,
+    opts: &PathTranslationOptions,
 ) -> Result<PathTranslation, PathError> {
     if path.trim().is_empty() {
         return Err(PathError::EmptyPath);
@@ -250,19 +303,19 @@ pub fn translate_path(
     // Determine translation direction based on OS types
     let translated = if from_os == Os::Windows && to_os.is_unix_like() {
         // Windows -> Unix
-        windows_to_unix(path, &mut result)
+        windows_to_unix(path, opts, &mut result)
     } else if from_os.is_unix_like() && to_os == Os::Windows {
         // Unix -> Windows
-        unix_to_windows(path, &mut result)
+        unix_to_windows(path, opts, &mut result)
     } else if from_os.is_unix_like() && to_os.is_unix_like() {
         // Unix -> Unix (just normalize)
         path.to_string()
     } else {
         // Fallback: try to auto-detect and convert
         if is_windows_path(path) {
-            windows_to_unix(path, &mut result)
+            windows_to_unix(path, opts, &mut result)
         } else {
-            unix_to_windows(path, &mut result)
+            unix_to_windows(path, opts, &mut result)
         }
     };
     
@@ -313,6 +366,93 @@ pub fn translate_path_auto(
     translate_path(path, from_os, to_os)
 }
 
+/// Auto-detect the path format and translate, failing when the format is unclear
+///
+/// Unlike [`translate_path_auto`], which falls back to Linux, this returns
+/// `Err(PathError::Ambiguous)` for paths that read the same on both families,
+/// such as a bare `file.txt` or a path mixing `\` and `/` separators.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_path_auto_strict, Os, PathError};
+///
+/// assert!(translate_path_auto_strict("C:\\Users\\john", Os::Linux).is_ok());
+/// assert!(matches!(translate_path_auto_strict("file.txt", Os::Linux), Err(PathError::Ambiguous(_))));
+/// ```
+pub fn translate_path_auto_strict(
+    path: &str,
+    to_os: Os,
+) -> Result<PathTranslation, PathError> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(PathError::EmptyPath);
+    }
+
+    let bytes = trimmed.as_bytes();
+    let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    let has_backslash = trimmed.contains('\\');
+    let has_slash = trimmed.contains('/');
+
+    let from_os = if has_drive || trimmed.starts_with("\\\\") || (has_backslash && !has_slash) {
+        Os::Windows
+    } else if has_slash && !has_backslash {
+        Os::Linux
+    } else {
+        return Err(PathError::Ambiguous(trimmed.to_string()));
+    };
+
+    translate_path(path, from_os, to_os)
+}
+
+/// Translate a path list such as `PATH` between operating systems
+///
+/// The list separator is converted (`;` on Windows, `:` elsewhere) and each
+/// entry is translated in place, so the precedence order is preserved.
+/// Entries that are variable references (`$PATH`, `%PATH%`) are converted
+/// to the target variable syntax rather than treated as paths.
+///
+/// # Example
+///
+/// ```
+/// use cmdx::{translate_path_list, Os};
+///
+/// let result = translate_path_list("C:\\tools;%PATH%", Os::Windows, Os::Linux);
+/// assert_eq!(result.path, "/mnt/c/tools:$PATH");
+/// ```
+pub fn translate_path_list(list: &str, from_os: Os, to_os: Os) -> PathTranslation {
+    let mut result = PathTranslation::new(String::new(), list.to_string(), from_os, to_os);
+    let (from_sep, to_sep) = (list_separator(from_os), list_separator(to_os));
+
+    let entries: Vec<String> = list
+        .split(from_sep)
+        .map(|entry| {
+            if entry.is_empty() {
+                return String::new();
+            }
+            if entry.starts_with('$') || (entry.starts_with('%') && entry.ends_with('%')) {
+                return translate_env_vars(entry, from_os, to_os);
+            }
+            match translate_path(entry, from_os, to_os) {
+                Ok(translated) => {
+                    result.drive_translated |= translated.drive_translated;
+                    result.warnings.extend(translated.warnings);
+                    translated.path
+                }
+                Err(_) => entry.to_string(),
+            }
+        })
+        .collect();
+
+    result.path = entries.join(&to_sep.to_string());
+    result
+}
+
+/// Separator used between entries of a path list like `PATH`
+fn list_separator(os: Os) -> char {
+    if os == Os::Windows { ';' } else { ':' }
+}
+
 /// Batch translate multiple paths
 pub fn translate_paths(
     paths: &[&str],
@@ -456,6 +596,15 @@ mod tests {
         assert_eq!(result.unwrap().path, "/home/john");
     }
 
+    #[test]
+    fn test_translate_path_list_preserves_order() {
+        let result = translate_path_list("/opt/bin:$PATH:/usr/local/bin", Os::Linux, Os::Windows);
+        assert_eq!(result.path, "C:\\opt\\bin;%PATH%;C:\\usr\\local\\bin");
+
+        let result = translate_path_list("%PATH%;D:\\bin", Os::Windows, Os::Linux);
+        assert_eq!(result.path, "$PATH:/mnt/d/bin");
+    }
+
     #[test]
     fn test_macos_to_windows() {
         let result = translate_path("/Users/john/Documents", Os::MacOS, Os::Windows);
@@ -463,4 +612,43 @@ mod tests {
         // macOS /Users maps to C:\Users on Windows
         assert!(result.unwrap().path.contains("Users"));
     }
+
+    #[test]
+    fn test_translate_path_auto_strict() {
+        let result = translate_path_auto_strict("C:\\Users\\john", Os::Linux).unwrap();
+        assert_eq!(result.path, "/mnt/c/Users/john");
+        let result = translate_path_auto_strict("/home/john", Os::Windows).unwrap();
+        assert_eq!(result.from_os, Os::Linux);
+
+        for ambiguous in ["file.txt", "Makefile", "dir\\sub/file"] {
+            assert!(matches!(
+                translate_path_auto_strict(ambiguous, Os::Windows),
+                Err(PathError::Ambiguous(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_cygwin_and_msys_mounts_to_windows() {
+        let result = translate_path("/cygdrive/c/Users", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.path, "C:\\Users");
+        assert!(result.drive_translated);
+        let result = translate_path("/c/Users", Os::Linux, Os::Windows).unwrap();
+        assert_eq!(result.path, "C:\\Users");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_path_with_mount_prefix() {
+        let msys = PathTranslationOptions { mount_prefix: "/".to_string() };
+        let result = translate_path_with_options("D:\\src", Os::Windows, Os::Linux, &msys).unwrap();
+        assert_eq!(result.path, "/d/src");
+
+        let cygwin = PathTranslationOptions { mount_prefix: "/cygdrive".to_string() };
+        let result = translate_path_with_options("C:\\Users", Os::Windows, Os::Linux, &cygwin).unwrap();
+        assert_eq!(result.path, "/cygdrive/c/Users");
+        // Longer directory names under the root aren't drives
+        let result = translate_path_with_options("/usr/bin", Os::Linux, Os::Windows, &cygwin).unwrap();
+        assert_eq!(result.path, "C:\\usr\\bin");
+    }
 }
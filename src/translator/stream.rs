@@ -0,0 +1,323 @@
+//! Streaming script translation module
+//!
+//! This module translates scripts line by line from any reader to any writer,
+//! so large scripts never have to be held in memory.
+//!
+//! ## Examples
+//!
+//! ```
+//! use std::io::Cursor;
+//! use cmdx::{translate_reader, Os};
+//!
+//! let mut output = Vec::new();
+//! let stats = translate_reader(Cursor::new("@echo off\ndir /w\n"), &mut output, Os::Windows, Os::Linux).unwrap();
+//! assert_eq!(String::from_utf8(output).unwrap(), "#!/bin/bash\nls -C\n");
+//! assert_eq!(stats.translated, 2);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use super::engine::{
+    translate_compound_command, translate_operator, translate_shebang, TranslationResult, COMPOUND_OPERATORS,
+};
+use super::os::Os;
+use super::package::{detect_package_manager, translate_package_compound_with, PackageManager};
+
+/// Line counts from a [`translate_reader`] run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationStats {
+    /// Lines written in translated form
+    pub translated: usize,
+    /// Blank and comment lines, which need no command translation
+    pub skipped: usize,
+    /// Lines that couldn't be translated and were written unchanged
+    pub errored: usize,
+}
+
+impl TranslationStats {
+    /// Total number of lines read
+    pub fn total(&self) -> usize {
+        self.translated + self.skipped + self.errored
+    }
+}
+
+/// Unmapped flags found across a script, for review before running it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationReport {
+    /// Each unique flag that wasn't translated, with the 1-based line numbers
+    /// it appeared on
+    pub unmapped_flags: BTreeMap<String, Vec<usize>>,
+}
+
+impl TranslationReport {
+    /// Record the unmapped flags of one translated line
+    pub fn record(&mut self, line: usize, result: &TranslationResult) {
+        for flag in &result.unmapped_flags {
+            let lines = self.unmapped_flags.entry(flag.clone()).or_default();
+            if lines.last() != Some(&line) {
+                lines.push(line);
+            }
+        }
+    }
+
+    /// Check whether no unmapped flags were found
+    pub fn is_empty(&self) -> bool {
+        self.unmapped_flags.is_empty()
+    }
+}
+
+impl fmt::Display for TranslationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, lines) in &self.unmapped_flags {
+            let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+            writeln!(f, "{}: line {}", flag, lines.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Translate a script line by line from `reader` to `writer`
+///
+/// Each line is translated as a compound command; lines that fail are written
+/// unchanged and counted as errored. The first line's shebang or `@echo off`
+/// is translated too, and comment markers (`#`, `rem`, `::`) are converted.
+/// Output uses the target's line endings.
+pub fn translate_reader<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    from_os: Os,
+    to_os: Os,
+) -> io::Result<TranslationStats> {
+    translate_lines(reader, writer, from_os, to_os, None, None)
+}
+
+/// [`translate_reader`] that also collects a [`TranslationReport`]
+pub fn translate_reader_with_report<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    from_os: Os,
+    to_os: Os,
+) -> io::Result<(TranslationStats, TranslationReport)> {
+    let mut report = TranslationReport::default();
+    let stats = translate_lines(reader, writer, from_os, to_os, None, Some(&mut report))?;
+    Ok((stats, report))
+}
+
+/// [`translate_reader`] that also moves package commands to another package manager
+///
+/// Lines that invoke a package manager (`apt install -y curl`) are rewritten
+/// for `package_manager`, e.g. to run an Ubuntu install script on Fedora
+/// with [`PackageManager::Dnf`]. Other lines get the usual command translation.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use cmdx::{translate_reader_with_packages, Os, PackageManager};
+///
+/// let mut output = Vec::new();
+/// translate_reader_with_packages(Cursor::new("apt update\n"), &mut output, Os::Linux, Os::Linux, PackageManager::Dnf).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "dnf makecache\n");
+/// ```
+pub fn translate_reader_with_packages<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    from_os: Os,
+    to_os: Os,
+    package_manager: PackageManager,
+) -> io::Result<TranslationStats> {
+    translate_lines(reader, writer, from_os, to_os, Some(package_manager), None)
+}
+
+fn translate_lines<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    from_os: Os,
+    to_os: Os,
+    package_manager: Option<PackageManager>,
+    mut report: Option<&mut TranslationReport>,
+) -> io::Result<TranslationStats> {
+    let newline = if to_os == Os::Windows { "\r\n" } else { "\n" };
+    let mut stats = TranslationStats::default();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        let trimmed = line.trim();
+
+        let output = if index == 0 && is_script_header(trimmed) {
+            stats.translated += 1;
+            translate_shebang(trimmed, from_os, to_os)
+        } else if from_os == Os::Windows && to_os != Os::Windows && is_echo_state(trimmed) {
+            // Shells don't echo commands, so there is nothing to switch
+            stats.skipped += 1;
+            continue;
+        } else if trimmed.is_empty() {
+            stats.skipped += 1;
+            line.to_string()
+        } else if let Some(text) = comment_text(trimmed, from_os) {
+            stats.skipped += 1;
+            format_comment(text, to_os)
+        } else if let Some(command) = package_manager.and_then(|to| translate_package_line(line, from_os, to_os, to)) {
+            stats.translated += 1;
+            command
+        } else {
+            match translate_compound_command(line, from_os, to_os) {
+                Ok(result) => {
+                    stats.translated += 1;
+                    if let Some(report) = report.as_deref_mut() {
+                        report.record(index + 1, &result);
+                    }
+                    result.command
+                }
+                Err(_) => {
+                    stats.errored += 1;
+                    line.to_string()
+                }
+            }
+        };
+
+        writer.write_all(output.as_bytes())?;
+        writer.write_all(newline.as_bytes())?;
+    }
+
+    writer.flush()?;
+    Ok(stats)
+}
+
+/// Translate a line of package commands, if it starts with one
+///
+/// The rest of the line gets the usual command translation between the OSes.
+fn translate_package_line(line: &str, from_os: Os, to_os: Os, to: PackageManager) -> Option<String> {
+    let from = detect_package_manager(line)?;
+    let translate_other = |part: &str| {
+        if COMPOUND_OPERATORS.contains(&part) {
+            translate_operator(part, from_os, to_os).to_string()
+        } else {
+            translate_compound_command(part, from_os, to_os).map_or_else(|_| part.to_string(), |result| result.command)
+        }
+    };
+    translate_package_compound_with(line, from, to, translate_other).ok().map(|result| result.command)
+}
+
+/// Check for a shebang or batch `@echo off` header
+fn is_script_header(line: &str) -> bool {
+    line.starts_with("#!") || line.eq_ignore_ascii_case("@echo off")
+}
+
+/// Check for a batch `echo off` / `echo on` line, with or without `@`
+fn is_echo_state(line: &str) -> bool {
+    let lower = line.trim_start_matches('@').to_lowercase();
+    matches!(lower.split_whitespace().collect::<Vec<_>>()[..], ["echo", "off" | "on"])
+}
+
+/// The text of a comment line in the source OS's script syntax
+fn comment_text(line: &str, from_os: Os) -> Option<&str> {
+    if from_os == Os::Windows {
+        let lower = line.to_lowercase();
+        if lower == "rem" || lower.starts_with("rem ") || lower.starts_with("@rem ") {
+            return Some(line[line.find(' ').unwrap_or(line.len())..].trim_start());
+        }
+        line.strip_prefix("::").map(str::trim_start)
+    } else {
+        line.strip_prefix('#').map(str::trim_start)
+    }
+}
+
+/// Write comment text with the target OS's comment marker
+fn format_comment(text: &str, to_os: Os) -> String {
+    let marker = if to_os == Os::Windows { "rem" } else { "#" };
+    if text.is_empty() {
+        marker.to_string()
+    } else {
+        format!("{} {}", marker, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn translate(input: &str, from_os: Os, to_os: Os) -> (String, TranslationStats) {
+        let mut output = Vec::new();
+        let stats = translate_reader(Cursor::new(input), &mut output, from_os, to_os).unwrap();
+        (String::from_utf8(output).unwrap(), stats)
+    }
+
+    #[test]
+    fn test_translate_reader_batch_to_shell() {
+        let input = "@echo off\r\nrem list files\r\ndir /w\r\n\r\ncls && dir /s\r\n";
+        let (output, stats) = translate(input, Os::Windows, Os::Linux);
+        assert_eq!(output, "#!/bin/bash\n# list files\nls -C\n\nclear && ls -R\n");
+        assert_eq!(stats, TranslationStats { translated: 3, skipped: 2, errored: 0 });
+        assert_eq!(stats.total(), 5);
+    }
+
+    #[test]
+    fn test_translate_reader_shell_to_batch() {
+        let input = "#!/bin/sh\n# cleanup\nls -la\nclear\n";
+        let (output, stats) = translate(input, Os::Linux, Os::Windows);
+        assert_eq!(output, "@echo off\r\nrem cleanup\r\ndir /a\r\ncls\r\n");
+        assert_eq!(stats, TranslationStats { translated: 3, skipped: 1, errored: 0 });
+    }
+
+    #[test]
+    fn test_translate_reader_keeps_failed_lines() {
+        let (output, stats) = translate("cat <<EOF\nls\n", Os::Linux, Os::Windows);
+        assert_eq!(output, "cat <<EOF\r\ndir\r\n");
+        assert_eq!(stats, TranslationStats { translated: 1, skipped: 0, errored: 1 });
+    }
+
+    #[test]
+    fn test_translate_reader_path_append() {
+        let input = "@echo off\r\nset PATH=%PATH%;C:\\tools\\bin\r\n";
+        let (output, stats) = translate(input, Os::Windows, Os::Linux);
+        assert_eq!(output, "#!/bin/bash\nexport PATH=\"$PATH:/mnt/c/tools/bin\"\n");
+        assert_eq!(stats, TranslationStats { translated: 2, skipped: 0, errored: 0 });
+    }
+
+    #[test]
+    fn test_translate_reader_echo_idioms() {
+        let input = "@echo off\r\necho.\r\n@echo on\r\necho done\r\n";
+        let (output, stats) = translate(input, Os::Windows, Os::Linux);
+        assert_eq!(output, "#!/bin/bash\necho \"\"\necho done\n");
+        assert_eq!(stats, TranslationStats { translated: 3, skipped: 1, errored: 0 });
+    }
+
+    #[test]
+    fn test_translate_reader_with_report() {
+        let script = "dir /z\ncls\ndir /z /y\ntype /q file && dir /z\n";
+        let mut output = Vec::new();
+        let (stats, report) =
+            translate_reader_with_report(Cursor::new(script), &mut output, Os::Windows, Os::Linux).unwrap();
+
+        assert_eq!(stats.translated, 4);
+        assert_eq!(report.unmapped_flags.len(), 3);
+        assert_eq!(report.unmapped_flags["/z"], vec![1, 3, 4]);
+        assert_eq!(report.unmapped_flags["/y"], vec![3]);
+        assert_eq!(report.unmapped_flags["/q"], vec![4]);
+        assert!(report.to_string().contains("/z: line 1, 3, 4"));
+    }
+
+    #[test]
+    fn test_translate_reader_with_report_bundled_flag() {
+        let mut output = Vec::new();
+        let (_, report) =
+            translate_reader_with_report(Cursor::new("ls -laZ\n"), &mut output, Os::Linux, Os::Windows).unwrap();
+        assert_eq!(report.unmapped_flags.len(), 1);
+        assert_eq!(report.unmapped_flags["-Z"], vec![1]);
+    }
+
+    #[test]
+    fn test_translate_reader_with_packages_across_oses() {
+        let mut output = Vec::new();
+        let script = "apt update && cd /tmp ; ls -la\n";
+        translate_reader_with_packages(Cursor::new(script), &mut output, Os::Linux, Os::Windows, PackageManager::Winget)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "winget source update && cd /tmp & dir /a\r\n");
+    }
+}
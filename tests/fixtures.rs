@@ -0,0 +1,95 @@
+//! Data-driven translation tests
+//!
+//! Every `tests/fixtures/*.txt` file holds lines of the form
+//! `input | from | to | expected`, run through `translate_full`.
+
+use std::fs;
+use std::path::Path;
+
+use cmdx::{translate_full, Os};
+
+const SEPARATOR: &str = " | ";
+
+struct Fixture {
+    location: String,
+    input: String,
+    from: Os,
+    to: Os,
+    expected: String,
+}
+
+/// Parse one fixture line; the first two consecutive OS fields split input from expected
+fn parse_line(line: &str, location: String) -> Result<Fixture, String> {
+    let fields: Vec<&str> = line.split(SEPARATOR).collect();
+    let os_index = (1..fields.len().saturating_sub(2))
+        .find(|&i| Os::parse(fields[i].trim()).is_some() && Os::parse(fields[i + 1].trim()).is_some())
+        .ok_or_else(|| format!("{}: expected `input | from | to | expected`", location))?;
+
+    Ok(Fixture {
+        location,
+        input: fields[..os_index].join(SEPARATOR),
+        from: Os::parse(fields[os_index].trim()).unwrap(),
+        to: Os::parse(fields[os_index + 1].trim()).unwrap(),
+        expected: fields[os_index + 2..].join(SEPARATOR),
+    })
+}
+
+fn load_fixtures(dir: &Path) -> Vec<Fixture> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .expect("fixtures directory")
+        .map(|entry| entry.expect("fixture entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    files.sort();
+
+    let mut fixtures = Vec::new();
+    for file in files {
+        let contents = fs::read_to_string(&file).expect("readable fixture file");
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim_end();
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let location = format!("{}:{}", file.display(), number + 1);
+            fixtures.push(parse_line(line, location).unwrap_or_else(|e| panic!("{}", e)));
+        }
+    }
+    fixtures
+}
+
+#[test]
+fn fixture_translations() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    let fixtures = load_fixtures(&dir);
+    assert!(!fixtures.is_empty(), "no fixtures found in {}", dir.display());
+
+    let mismatches: Vec<String> = fixtures
+        .iter()
+        .filter_map(|f| {
+            let actual = match translate_full(&f.input, f.from, f.to) {
+                Ok(result) => result.command,
+                Err(e) => format!("error: {}", e),
+            };
+            (actual != f.expected).then(|| {
+                format!("{}: `{}`\n    expected: `{}`\n    actual:   `{}`", f.location, f.input, f.expected, actual)
+            })
+        })
+        .collect();
+
+    assert!(
+        mismatches.is_empty(),
+        "{} of {} fixtures failed:\n{}",
+        mismatches.len(),
+        fixtures.len(),
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn parse_line_allows_pipes_in_commands() {
+    let fixture = parse_line("ls | grep x | windows | linux | ls | grep x", "test".to_string()).unwrap();
+    assert_eq!(fixture.input, "ls | grep x");
+    assert_eq!(fixture.from, Os::Windows);
+    assert_eq!(fixture.to, Os::Linux);
+    assert_eq!(fixture.expected, "ls | grep x");
+}
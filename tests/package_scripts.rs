@@ -0,0 +1,20 @@
+//! End-to-end translation of install scripts between package managers
+
+use std::io::Cursor;
+
+use cmdx::{translate_reader_with_packages, Os, PackageManager};
+
+#[test]
+fn apt_script_to_dnf() {
+    let script = "sudo apt update\nsudo apt install -y git curl\nls -la /opt\n";
+    let target = PackageManager::for_distro("fedora").unwrap();
+
+    let mut output = Vec::new();
+    let stats = translate_reader_with_packages(Cursor::new(script), &mut output, Os::Linux, Os::Linux, target).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "sudo dnf makecache\nsudo dnf install -y git curl\nls -la /opt\n"
+    );
+    assert_eq!(stats.translated, 3);
+}
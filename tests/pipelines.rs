@@ -0,0 +1,19 @@
+//! End-to-end translation of common multi-command pipelines
+
+use cmdx::{translate_compound_command, Os};
+
+#[test]
+fn process_search_pipeline_to_windows() {
+    let result = translate_compound_command("ps aux | grep nginx", Os::Linux, Os::Windows).unwrap();
+    assert_eq!(result.command, "tasklist | findstr nginx");
+    assert!(!result.had_unmapped_flags);
+
+    let result = translate_compound_command("ps -ef | grep -i nginx", Os::Linux, Os::Windows).unwrap();
+    assert_eq!(result.command, "tasklist | findstr /i nginx");
+}
+
+#[test]
+fn process_search_pipeline_to_linux() {
+    let result = translate_compound_command("tasklist | findstr nginx", Os::Windows, Os::Linux).unwrap();
+    assert_eq!(result.command, "ps aux | grep nginx");
+}